@@ -1,76 +1,33 @@
-#![feature(const_generics)]
-#![feature(const_evaluatable_checked)]
-#![allow(incomplete_features)]
-
+use upper_tri::dimension::StaticDimension;
 use upper_tri::dimension::StcSquare;
-use upper_tri::upper_tri::upper_tri_stc::*;
-use upper_tri::upper_tri::*;
-
-const ONE: usize = 1;
-
-const fn minus_one(n: usize) -> Option<usize> {
-    if n == 0 {
-        None
-    } else {
-        Some(n - 1)
-    }
-}
+use upper_tri::upper_tri::strict_upper_tri::StrictUpperTri;
 
-struct SoN<const N: usize>(UpperTriRawData<f64, StcSquare<{ N - 1 }>>)
-where
-    StcSquare<{ N - 1 }>: Sized;
+/// so(n) for the static dimension `D`. An n×n antisymmetric matrix is determined by its entries
+/// strictly above the diagonal
+struct SoN<D: StaticDimension>(StrictUpperTri<f64, D>);
 
-impl<const N: usize> SoN<N>
-where
-    StcSquare<{ N - 1 }>: Sized,
-{
-    fn new() -> SoN<N> {
-        let data: UpperTriRawData<f64, StcSquare<{ N - 1 }>> =
-            UpperTriRawData::<f64, StcSquare<{ N - 1 }>>::new();
-        SoN(data)
+impl<D: StaticDimension> SoN<D> {
+    fn new() -> Self {
+        SoN(StrictUpperTri::<f64, D>::new())
     }
-}
 
-impl<const N: usize> SoN<N>
-where
-    StcSquare<{ N - 1 }>: Sized,
-{
-    fn get_row<'a>(&'a self, row: usize) -> Box<dyn Iterator<Item = f64> + 'a> {
-        let diag = self.0.get_diag_el(row).map(|x| *x).into_iter();
-        let row_iter = self.0.get_raw_row(row).map(|x| *x);
-        let end = std::iter::once(0.0).chain(diag).chain(row_iter);
-
-        if row == 0 {
-            let b = std::iter::empty().chain(end);
-            Box::new(b)
-        } else {
-            let index = row - 1;
-            let col = self.0.get_raw_col(index).map(|x| -x);
-            let diag = self.0.get_diag_el(index).into_iter().map(|x| -x);
-            let c = col.chain(diag).chain(end);
-            Box::new(c)
-        }
+    fn get_row<'a>(&'a self, row: usize) -> impl Iterator<Item = f64> + 'a {
+        let col = self.0.get_raw_col(row).map(|x| -x);
+        let row_iter = self.0.get_raw_row(row).copied();
+        col.chain(std::iter::once(0.0)).chain(row_iter)
     }
 
-    fn get_col<'a>(&'a self, col: usize) -> Box<dyn Iterator<Item = f64> + 'a> {
-        Box::new(self.get_row(col).map(|x| -x))
+    fn get_col<'a>(&'a self, col: usize) -> impl Iterator<Item = f64> + 'a {
+        self.get_row(col).map(|x| -x)
     }
 }
 
-fn lie_prod<'a, const N: usize>(a: &'a SoN<N>, b: &'a SoN<N>) -> SoN<N>
-where
-    StcSquare<{ N - 1 }>: Sized,
-{
-    let iter = (0..N).flat_map(|j| {
-        (0..j).map(move |i| {
-            let left: f64 = a.get_row(i).zip(b.get_col(j)).map(|(x, y)| x * y).sum();
-            let right: f64 = b.get_row(i).zip(a.get_col(j)).map(|(x, y)| x * y).sum();
-            left - right
-        })
+fn lie_prod<D: StaticDimension>(a: &SoN<D>, b: &SoN<D>) -> SoN<D> {
+    let data_c = StrictUpperTri::<f64, D>::from_fn(|i, j| {
+        let left: f64 = a.get_row(i).zip(b.get_col(j)).map(|(x, y)| x * y).sum();
+        let right: f64 = b.get_row(i).zip(a.get_col(j)).map(|(x, y)| x * y).sum();
+        left - right
     });
-
-    let mut data_c = UpperTriRawData::<f64, StcSquare<{ N - 1 }>>::new();
-    data_c.iter_mut().zip(iter).for_each(|(c, new)| *c = new);
     SoN(data_c)
 }
 
@@ -81,16 +38,16 @@ macro_rules! l {
 }
 
 fn main() {
-    let mut left_data = SoN::<3>::new();
-    let mut right_data = SoN::<3>::new();
+    let mut left_data = SoN::<StcSquare<3>>::new();
+    let mut right_data = SoN::<StcSquare<3>>::new();
 
-    let mut output_should = SoN::<3>::new();
-    *left_data.0.get_mut(0, 0).unwrap() = 1.0;
-    *right_data.0.get_mut(1, 1).unwrap() = 1.0;
+    let mut output_should = SoN::<StcSquare<3>>::new();
+    left_data.0[(0, 1)] = 1.0;
+    right_data.0[(1, 2)] = 1.0;
 
     println!("{}", left_data.0);
     println!("{}", right_data.0);
-    *output_should.0.get_mut(0, 1).unwrap() = 1.0;
+    output_should.0[(0, 2)] = 1.0;
     let output = l![&left_data, &right_data];
     println!("{}", output.0);
     println!("{}", output_should.0);
@@ -13,6 +13,7 @@ impl SquareDimension for DynSquare {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl DynSquare {
     pub(crate) fn grow(&mut self) {
         self.0 += 1;
@@ -33,3 +34,86 @@ impl<const N: usize> SquareDimension for StcSquare<N> {
         N
     }
 }
+
+/// Dimensions fixed at compile time. Any rank works for storage and access, but growing or
+/// shrinking a static matrix goes through `HasNext` and `HasPrev`, which stop at rank 64
+pub trait StaticDimension: SquareDimension + sealed::Sealed {
+    const RANK: usize;
+    const DIM: Self;
+}
+
+impl<const N: usize> StaticDimension for StcSquare<N> {
+    const RANK: usize = N;
+    const DIM: Self = StcSquare;
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::DynSquare {}
+
+    impl<const N: usize> Sealed for super::StcSquare<N> {}
+}
+
+/// A dimension with one fewer row and column. Stable Rust can't name `StcSquare<{ N - 1 }>` for
+/// a generic `N`, so static dimensions implement this for each rank from 1 to 64 and no further.
+/// The dynamic previous of rank 0 is rank 0, as with shrinking
+pub trait HasPrev: SquareDimension + sealed::Sealed {
+    type Prev: SquareDimension;
+
+    fn prev(&self) -> Self::Prev;
+}
+
+/// A dimension with one more row and column, implemented for static ranks 0 to 63. A static
+/// matrix of rank 64 or more can't be grown in place; convert it to `DynSquare` first
+pub trait HasNext: SquareDimension + sealed::Sealed {
+    type Next: SquareDimension;
+
+    fn next(&self) -> Self::Next;
+}
+
+impl HasPrev for DynSquare {
+    type Prev = DynSquare;
+
+    fn prev(&self) -> DynSquare {
+        DynSquare(self.0.saturating_sub(1))
+    }
+}
+
+impl HasNext for DynSquare {
+    type Next = DynSquare;
+
+    fn next(&self) -> DynSquare {
+        DynSquare(self.0 + 1)
+    }
+}
+
+/// Chains `HasNext` and `HasPrev` between each pair of neighbouring ranks. Extending the list
+/// raises the rank limit documented on those traits
+macro_rules! static_neighbours {
+    ($rank:literal $next:literal $($rest:literal)*) => {
+        impl HasNext for StcSquare<$rank> {
+            type Next = StcSquare<$next>;
+
+            fn next(&self) -> Self::Next {
+                StcSquare
+            }
+        }
+
+        impl HasPrev for StcSquare<$next> {
+            type Prev = StcSquare<$rank>;
+
+            fn prev(&self) -> Self::Prev {
+                StcSquare
+            }
+        }
+
+        static_neighbours!($next $($rest)*);
+    };
+    ($last:literal) => {};
+}
+
+static_neighbours!(
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32 33 34
+    35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63 64
+);
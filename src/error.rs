@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+/// Errors from operations whose arguments have to agree with the shape of a matrix
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum UpperTriError {
+    #[error("rank mismatch: expected {expected}, found {found}")]
+    RankMismatch { expected: usize, found: usize },
+    #[error("not a permutation of 0..{rank}")]
+    InvalidPermutation { rank: usize },
+    #[error("index {index} out of range for rank {rank}")]
+    IndexOutOfRange { index: usize, rank: usize },
+    /// A packed buffer or right hand side whose length doesn't fit the rank
+    #[error("length mismatch: expected {expected}, found {found}")]
+    LengthMismatch { expected: usize, found: usize },
+}
@@ -1,4 +1,15 @@
+//! Column packed upper triangular matrices. The crate is `no_std`; the `alloc` feature enables
+//! the owned, heap allocated matrices and `std` (on by default) adds `std::error::Error`.
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
 pub mod dimension;
+pub mod error;
+pub mod storage;
 pub mod upper_tri;
 #[cfg(test)]
 mod tests {
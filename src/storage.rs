@@ -0,0 +1,85 @@
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+use alloc::sync::Arc;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Contiguous storage for the column packed elements of a matrix. Implement this for a wrapper
+/// to back a matrix with memory the crate doesn't own, such as a memory map or a pooled buffer.
+/// The length is checked against the rank when the matrix is built
+pub trait PackedStorage<T> {
+    fn as_slice(&self) -> &[T];
+}
+
+/// Storage whose elements can be written in place. The length can't change through this, so
+/// resizing stays with `Vec`
+pub trait PackedStorageMut<T>: PackedStorage<T> {
+    fn as_mut_slice(&mut self) -> &mut [T];
+}
+
+impl<T, const L: usize> PackedStorage<T> for [T; L] {
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const L: usize> PackedStorageMut<T> for [T; L] {
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T> PackedStorage<T> for &[T] {
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> PackedStorage<T> for &mut [T] {
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> PackedStorageMut<T> for &mut [T] {
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> PackedStorage<T> for Vec<T> {
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> PackedStorageMut<T> for Vec<T> {
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> PackedStorage<T> for Box<[T]> {
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> PackedStorageMut<T> for Box<[T]> {
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+/// Shared read only snapshots. Clone the `Arc` to hand the same matrix to several readers
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl<T> PackedStorage<T> for Arc<[T]> {
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
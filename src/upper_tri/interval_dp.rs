@@ -0,0 +1,128 @@
+use crate::upper_tri::*;
+use alloc::boxed::Box;
+
+/// Read only access to the sub-intervals of the cell currently being computed
+pub struct Subintervals<'a, T> {
+    table: UpperTriView<'a, Option<T>, DynSquare>,
+    current: IndexPair,
+}
+
+impl<'a, T> Subintervals<'a, T> {
+    /// The interval being computed
+    pub fn current(&self) -> IndexPair {
+        self.current
+    }
+
+    /// The value of a strictly smaller interval inside the current one, or `None` for any other
+    /// interval
+    pub fn get(&self, row: usize, col: usize) -> Option<&'a T> {
+        let IndexPair {
+            row: start,
+            col: end,
+        } = self.current;
+        if row < start || col > end || (row == start && col == end) {
+            return None;
+        }
+        self.table.get(row, col)?.as_ref()
+    }
+}
+
+/// The optimal split tree of an interval as recorded by `IntervalDp`
+#[derive(Debug, PartialEq, Eq)]
+pub enum SplitTree {
+    Leaf(IndexPair),
+    Split {
+        interval: IndexPair,
+        left: Box<SplitTree>,
+        right: Box<SplitTree>,
+    },
+}
+
+/// A driver for interval dynamic programs such as CYK parsing, matrix chain ordering, optimal
+/// binary search trees or Nussinov folding, where cell (i, j) depends on (i, k) and (k + 1, j).
+/// Cells are filled in increasing span order, and each cell may record the split point `k` it
+/// was built from
+pub struct IntervalDp<T> {
+    values: UpperTriRawData<T, DynSquare>,
+    splits: UpperTriRawData<Option<usize>, DynSquare>,
+}
+
+impl<T> IntervalDp<T> {
+    /// Fill a table of the given rank. `cell` is called once for every interval and returns its
+    /// value together with the split point used, if any. A split `k` must satisfy
+    /// `row <= k < col`
+    pub fn solve<F>(rank: usize, mut cell: F) -> Self
+    where
+        F: FnMut(IndexPair, &Subintervals<'_, T>) -> (T, Option<usize>),
+    {
+        let size = rank * (rank + 1) / 2;
+        let mut values = UpperTriRawData::from_parts(
+            repeat_with(|| None).take(size).collect::<Vec<Option<T>>>(),
+            DynSquare(rank),
+        );
+        let mut splits = UpperTriRawData::from_parts(vec![None; size], DynSquare(rank));
+
+        for span in 0..rank {
+            for row in 0..(rank - span) {
+                let current = IndexPair {
+                    row,
+                    col: row + span,
+                };
+                let subintervals = Subintervals {
+                    table: values.as_view(),
+                    current,
+                };
+                let (value, split) = cell(current, &subintervals);
+                if let Some(k) = split {
+                    assert!(
+                        row <= k && k < current.col,
+                        "split {} outside of interval ({}, {})",
+                        k,
+                        row,
+                        current.col
+                    );
+                }
+                let offset = offset_for_col(current.col, row);
+                values.buf[offset] = Some(value);
+                splits.buf[offset] = split;
+            }
+        }
+
+        let values = UpperTriRawData::from_parts(
+            values.buf.into_iter().flatten().collect(),
+            DynSquare(rank),
+        );
+        IntervalDp { values, splits }
+    }
+
+    pub fn value(&self, row: usize, col: usize) -> Option<&T> {
+        self.values.as_view().get(row, col)
+    }
+
+    /// The split point recorded for the interval (row, col)
+    pub fn split(&self, row: usize, col: usize) -> Option<usize> {
+        self.splits.as_view().get(row, col).copied().flatten()
+    }
+
+    pub fn table(&self) -> &UpperTriRawData<T, DynSquare> {
+        &self.values
+    }
+
+    pub fn into_table(self) -> UpperTriRawData<T, DynSquare> {
+        self.values
+    }
+
+    /// Follow the recorded split points down from (row, col)
+    pub fn split_tree(&self, row: usize, col: usize) -> Option<SplitTree> {
+        self.value(row, col)?;
+        let interval = IndexPair { row, col };
+        match self.split(row, col) {
+            None => Some(SplitTree::Leaf(interval)),
+            Some(k) => Some(SplitTree::Split {
+                interval,
+                left: Box::new(self.split_tree(row, k)?),
+                right: Box::new(self.split_tree(k + 1, col)?),
+            }),
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use crate::upper_tri::solve::back_substitute;
+use crate::upper_tri::solve::forward_substitute;
+use crate::upper_tri::triangular::fmt_transposed_triangle;
+use crate::upper_tri::view::UpperTriViewMut;
+use crate::upper_tri::*;
+use core::ops::Sub;
+
+/// A row packed lower triangular matrix: element (row, col) with col <= row is at offset
+/// row * (row + 1) / 2 + col. That is the column packed layout of the upper triangular transpose,
+/// so the matrix is held as that transpose and `transpose` moves between the two without copying
+#[cfg(feature = "alloc")]
+pub struct LowerTriRawData<T, D: SquareDimension + Clone, S = Vec<T>> {
+    upper: UpperTriRawData<T, D, S>,
+}
+
+/// A row packed lower triangular matrix of rank `D` with its elements held in `S`
+#[cfg(not(feature = "alloc"))]
+pub struct LowerTriRawData<T, D: SquareDimension + Clone, S> {
+    upper: UpperTriRawData<T, D, S>,
+}
+
+// derived impls would require T: Clone
+impl<T, D: SquareDimension, S: Clone> Clone for LowerTriRawData<T, D, S> {
+    fn clone(&self) -> Self {
+        LowerTriRawData {
+            upper: self.upper.clone(),
+        }
+    }
+}
+
+impl<T, D: SquareDimension, S: Copy> Copy for LowerTriRawData<T, D, S> {}
+
+impl<T, D: SquareDimension, S> UpperTriRawData<T, D, S> {
+    /// The lower triangular transpose, sharing the buffer
+    pub fn transpose(self) -> LowerTriRawData<T, D, S> {
+        LowerTriRawData { upper: self }
+    }
+}
+
+impl<T, D: SquareDimension, S> LowerTriRawData<T, D, S> {
+    /// The upper triangular transpose, sharing the buffer
+    pub fn transpose(self) -> UpperTriRawData<T, D, S> {
+        self.upper
+    }
+
+    pub fn storage(&self) -> &S {
+        self.upper.storage()
+    }
+
+    pub fn into_storage(self) -> S {
+        self.upper.into_storage()
+    }
+}
+
+impl<T, S: PackedStorage<T>> LowerTriRawData<T, DynSquare, S> {
+    /// Take ownership of a row packed buffer, checking its length against the rank
+    pub fn from_packed(rank: usize, buf: S) -> Result<Self, UpperTriError> {
+        UpperTriRawData::<T, DynSquare, S>::from_packed(rank, buf).map(UpperTriRawData::transpose)
+    }
+}
+
+impl<T, D: StaticDimension, S: PackedStorage<T>> LowerTriRawData<T, D, S> {
+    /// Take ownership of a row packed buffer, checking its length against the rank
+    pub fn from_packed(buf: S) -> Result<Self, UpperTriError> {
+        UpperTriRawData::<T, D, S>::from_packed(buf).map(UpperTriRawData::transpose)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> LowerTriRawData<T, DynSquare> {
+    /// Build a matrix from the value at each position on or below the diagonal
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(rank: usize, mut f: F) -> Self {
+        UpperTriRawData::<T, DynSquare>::from_fn(rank, |row, col| f(col, row)).transpose()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Zero> LowerTriRawData<T, DynSquare> {
+    pub fn new(rank: usize) -> Self {
+        UpperTriRawData::<T, DynSquare>::new(rank).transpose()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, D: StaticDimension> LowerTriRawData<T, D> {
+    /// Build a matrix from the value at each position on or below the diagonal
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(mut f: F) -> Self {
+        UpperTriRawData::<T, D>::from_fn(|row, col| f(col, row)).transpose()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Zero, D: StaticDimension> LowerTriRawData<T, D> {
+    pub fn new() -> Self {
+        UpperTriRawData::<T, D>::new().transpose()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Zero, D: StaticDimension> Default for LowerTriRawData<T, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Anything holding row packed lower triangular elements. These are read through the column
+/// packed upper triangular transpose, so everything is provided through `as_upper` and code taking
+/// a `M: LowerTriangular<T>` can hand the transpose to the `TriangularMatrix` algorithms. Rows of
+/// the lower triangle are columns of the transpose, so row access is contiguous and column access
+/// is gapped
+pub trait LowerTriangular<T> {
+    type Dim: SquareDimension;
+
+    /// A view of the upper triangular transpose
+    fn as_upper(&self) -> UpperTriView<'_, T, Self::Dim>;
+
+    fn dim(&self) -> Self::Dim {
+        self.as_upper().rank
+    }
+
+    fn rank(&self) -> usize {
+        self.dim().to_usize()
+    }
+
+    /// The packed elements in row order
+    fn as_slice(&self) -> &[T] {
+        self.as_upper().as_slice()
+    }
+
+    fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.as_upper().get(col, row)
+    }
+
+    fn get_diag_el(&self, index: usize) -> Option<&T> {
+        self.as_upper().get_diag_el(index)
+    }
+
+    /// The elements left of the diagonal in `row`
+    fn get_raw_row(&self, row: usize) -> ColView<'_, T> {
+        self.as_upper().get_raw_col(row)
+    }
+
+    /// The elements below the diagonal in `col`
+    fn get_raw_col(&self, col: usize) -> RowView<'_, T> {
+        self.as_upper().get_raw_row(col)
+    }
+
+    fn get_diag(&self) -> DiagView<'_, T> {
+        self.as_upper().get_diag()
+    }
+
+    /// Row `row` up to and including the diagonal
+    fn row_slice(&self, row: usize) -> &[T] {
+        self.as_upper().col_slice(row)
+    }
+
+    fn rows(&self) -> Columns<'_, T> {
+        self.as_upper().columns()
+    }
+
+    fn iter(&self) -> Iter<'_, T> {
+        self.as_upper().iter()
+    }
+}
+
+/// A `LowerTriangular` whose elements can be written in place
+pub trait LowerTriangularMut<T>: LowerTriangular<T> {
+    fn as_upper_mut(&mut self) -> UpperTriViewMut<'_, T, Self::Dim>;
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self.as_upper_mut().into_slice()
+    }
+
+    fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.as_upper_mut().into_mut(col, row)
+    }
+
+    fn get_diag_el_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.as_upper_mut().into_mut(index, index)
+    }
+
+    fn get_raw_row_mut(&mut self, row: usize) -> ColViewMut<'_, T> {
+        self.as_upper_mut().into_raw_col_mut(row)
+    }
+
+    fn get_raw_col_mut(&mut self, col: usize) -> RowViewMut<'_, T> {
+        self.as_upper_mut().into_raw_row_mut(col)
+    }
+
+    fn get_diag_mut(&mut self) -> DiagViewMut<'_, T> {
+        self.as_upper_mut().into_diag_mut()
+    }
+
+    fn row_slice_mut(&mut self, row: usize) -> &mut [T] {
+        self.as_upper_mut().into_col_slice_mut(row)
+    }
+
+    fn rows_mut(&mut self) -> ColumnsMut<'_, T> {
+        self.as_upper_mut().into_columns_mut()
+    }
+
+    fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorage<T>> LowerTriangular<T> for LowerTriRawData<T, D, S> {
+    type Dim = D;
+
+    fn as_upper(&self) -> UpperTriView<'_, T, D> {
+        self.upper.as_view()
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorageMut<T>> LowerTriangularMut<T>
+    for LowerTriRawData<T, D, S>
+{
+    fn as_upper_mut(&mut self) -> UpperTriViewMut<'_, T, D> {
+        self.upper.as_view_mut()
+    }
+}
+
+/// Operations that build a new matrix, which is always backed by a `Vec`. Positions match
+/// elementwise between a matrix and its transpose, so these go through the upper versions
+#[cfg(feature = "alloc")]
+impl<T, D: SquareDimension, S: PackedStorage<T>> LowerTriRawData<T, D, S> {
+    pub fn map<B, F: FnMut(&T) -> B>(&self, f: F) -> LowerTriRawData<B, D> {
+        self.upper.map(f).transpose()
+    }
+
+    /// Combine two matrices of the same rank elementwise into a new matrix
+    pub fn zip_map<U, M, B, F>(
+        &self,
+        other: &M,
+        f: F,
+    ) -> Result<LowerTriRawData<B, D>, UpperTriError>
+    where
+        M: LowerTriangular<U, Dim = D>,
+        F: FnMut(&T, &U) -> B,
+    {
+        self.upper
+            .zip_map(&other.as_upper(), f)
+            .map(UpperTriRawData::transpose)
+    }
+
+    pub fn hadamard<M: LowerTriangular<T, Dim = D>>(
+        &self,
+        other: &M,
+    ) -> Result<LowerTriRawData<T, D>, UpperTriError>
+    where
+        T: Clone + Mul<Output = T>,
+    {
+        self.upper
+            .hadamard(&other.as_upper())
+            .map(UpperTriRawData::transpose)
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorage<T>> LowerTriRawData<T, D, S>
+where
+    T: Clone + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+{
+    /// Solve `L x = b` by forward substitution, overwriting `b` with `x`
+    pub fn solve_inplace(&self, b: &mut [T]) -> Result<(), UpperTriError> {
+        forward_substitute(self, b)
+    }
+
+    /// Solve `Lᵀ x = b` by back substitution, overwriting `b` with `x`. With a Cholesky factor
+    /// this follows `solve_inplace` to solve `L Lᵀ x = b`
+    pub fn solve_transposed_inplace(&self, b: &mut [T]) -> Result<(), UpperTriError> {
+        back_substitute(&self.upper, b)
+    }
+}
+
+/// Panics if the position is out of range or above the diagonal, like `get` returning `None`
+impl<T, D: SquareDimension, S: PackedStorage<T>> Index<(usize, usize)>
+    for LowerTriRawData<T, D, S>
+{
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        let rank = self.rank();
+        match self.get(row, col) {
+            Some(t) => t,
+            None => panic!("index ({}, {}) out of range for rank {}", row, col, rank),
+        }
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorageMut<T>> IndexMut<(usize, usize)>
+    for LowerTriRawData<T, D, S>
+{
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let rank = self.rank();
+        match self.get_mut(row, col) {
+            Some(t) => t,
+            None => panic!("index ({}, {}) out of range for rank {}", row, col, rank),
+        }
+    }
+}
+
+impl<'a, T, D, S, M> AddAssign<&'a M> for LowerTriRawData<T, D, S>
+where
+    T: for<'b> AddAssign<&'b T>,
+    D: SquareDimension,
+    S: PackedStorageMut<T>,
+    M: LowerTriangular<T, Dim = D> + ?Sized,
+{
+    fn add_assign(&mut self, rhs: &'a M) {
+        self.upper += &rhs.as_upper();
+    }
+}
+
+impl<'a, T, D, S, M> SubAssign<&'a M> for LowerTriRawData<T, D, S>
+where
+    T: for<'b> SubAssign<&'b T>,
+    D: SquareDimension,
+    S: PackedStorageMut<T>,
+    M: LowerTriangular<T, Dim = D> + ?Sized,
+{
+    fn sub_assign(&mut self, rhs: &'a M) {
+        self.upper -= &rhs.as_upper();
+    }
+}
+
+impl<'a, T, D, S, M> Add<&'a M> for LowerTriRawData<T, D, S>
+where
+    T: for<'b> AddAssign<&'b T>,
+    D: SquareDimension,
+    S: PackedStorageMut<T>,
+    M: LowerTriangular<T, Dim = D> + ?Sized,
+{
+    type Output = Self;
+
+    fn add(mut self, rhs: &'a M) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+/// Elements are padded to the width of the widest one so the columns line up
+impl<T: Display, D: SquareDimension, S: PackedStorage<T>> Display for LowerTriRawData<T, D, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::result::Result<(), core::fmt::Error> {
+        fmt_transposed_triangle(&self.upper, f)
+    }
+}
@@ -1,426 +1,858 @@
 use crate::dimension::DynSquare;
 use crate::dimension::SquareDimension;
+use crate::dimension::StaticDimension;
+use crate::error::UpperTriError;
+use crate::storage::PackedStorage;
+use crate::storage::PackedStorageMut;
+pub use crate::upper_tri::lower_tri::LowerTriangular;
+pub use crate::upper_tri::lower_tri::LowerTriangularMut;
+use crate::upper_tri::triangular::fmt_triangle;
+pub use crate::upper_tri::triangular::TriangularMatrix;
+pub use crate::upper_tri::triangular::TriangularMatrixMut;
+use crate::upper_tri::view::UpperTriView;
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt::Display;
+#[cfg(feature = "alloc")]
+use core::iter::repeat;
+#[cfg(feature = "alloc")]
+use core::iter::repeat_n;
+#[cfg(feature = "alloc")]
+use core::iter::repeat_with;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ops::Add;
+use core::ops::AddAssign;
+#[cfg(feature = "alloc")]
+use core::ops::Deref;
+use core::ops::Div;
+use core::ops::Index;
+use core::ops::IndexMut;
+use core::ops::Mul;
+use core::ops::Neg;
+use core::ops::Range;
+use core::ops::SubAssign;
 use core::slice::Iter;
-use rand_distr::num_traits::Zero;
-use std::fmt::Display;
-use std::iter::repeat;
-use std::marker::PhantomData;
-use std::ops::Add;
-use std::ops::AddAssign;
-use std::ops::Deref;
-use std::ops::Range;
-use std::ops::SubAssign;
-use std::slice::IterMut;
-use std::vec::IntoIter;
+use core::slice::IterMut;
+use num_traits::Zero;
+#[cfg(feature = "alloc")]
+pub mod interval_dp;
+pub mod lower_tri;
+#[cfg(feature = "alloc")]
+pub mod permute;
+pub mod reduce;
+pub mod solve;
+pub mod strict_upper_tri;
+#[cfg(feature = "alloc")]
+pub mod submatrix;
+pub mod triangular;
+pub mod upper_tri_array;
+#[cfg(feature = "alloc")]
 pub mod upper_tri_dyn;
+#[cfg(feature = "alloc")]
 pub mod upper_tri_stc;
+pub mod view;
 
-#[derive(Clone)]
-pub struct UpperTriRawData<T, D: SquareDimension + Clone> {
-    buf: Vec<T>,
+/// A column packed upper triangular matrix of rank `D` with its elements held in `S`, which is
+/// a `Vec` unless another `PackedStorage` is named
+#[cfg(feature = "alloc")]
+pub struct UpperTriRawData<T, D: SquareDimension + Clone, S = Vec<T>> {
+    buf: S,
     pub rank: D,
+    marker: PhantomData<T>,
 }
 
-// Need to guarantee that row <= col
-#[derive(Clone, Copy)]
+/// A column packed upper triangular matrix of rank `D` with its elements held in `S`
+#[cfg(not(feature = "alloc"))]
+pub struct UpperTriRawData<T, D: SquareDimension + Clone, S> {
+    buf: S,
+    pub rank: D,
+    marker: PhantomData<T>,
+}
+
+// derived impls would require T: Clone through the marker, though only the storage is cloned
+impl<T, D: SquareDimension, S: Clone> Clone for UpperTriRawData<T, D, S> {
+    fn clone(&self) -> Self {
+        UpperTriRawData {
+            buf: self.buf.clone(),
+            rank: self.rank,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, D: SquareDimension, S: Copy> Copy for UpperTriRawData<T, D, S> {}
+
+/// A position in the upper triangle. Use `new` or `sorted` to guarantee that row <= col
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct IndexPair {
     pub row: usize,
     pub col: usize,
 }
 
+impl IndexPair {
+    /// `None` unless the position is on or above the diagonal
+    pub fn new(row: usize, col: usize) -> Option<Self> {
+        if row <= col {
+            Some(IndexPair { row, col })
+        } else {
+            None
+        }
+    }
+
+    /// The position of (i, j) or its mirror image, whichever is in the upper triangle
+    pub fn sorted(i: usize, j: usize) -> Self {
+        IndexPair {
+            row: i.min(j),
+            col: i.max(j),
+        }
+    }
+}
+
+/// The offset of `pair` in a column packed buffer
+pub fn offset_of(pair: IndexPair) -> usize {
+    offset_for_col(pair.col, pair.row)
+}
+
+/// The position of the element at `offset` in a column packed buffer, the inverse of `offset_of`.
+/// `8 * offset` overflows `usize` past rank 32k on 32 bit targets, so this works in `u128` and
+/// every `usize` offset has a position
+pub fn pair_of(offset: usize) -> IndexPair {
+    let offset = offset as u128;
+    // the largest col with col * (col + 1) / 2 <= offset
+    let col = ((8 * offset + 1).isqrt() - 1) / 2;
+    IndexPair {
+        row: (offset - col * (col + 1) / 2) as usize,
+        col: col as usize,
+    }
+}
+
 fn offset_for_col(col: usize, row: usize) -> usize {
     let col_offset = (col * (col + 1)) / 2;
     col_offset + row
 }
 
-/// A struct for reading the component strictly above the diagonal
-pub struct ColView<RefType, BaseType, D: SquareDimension> {
-    ptr: *const BaseType,
-    col_num: usize,
-    rank: D,
-    row: usize,
-    col_offset: usize,
-    _ref_type: PhantomData<RefType>,
+/// Fill a packed buffer of the given rank in column order from `f(row, col)`
+#[cfg(feature = "alloc")]
+fn packed_from_fn<T, F: FnMut(usize, usize) -> T>(rank: usize, mut f: F) -> Vec<T> {
+    let mut buf = Vec::with_capacity(rank * (rank + 1) / 2);
+    for col in 0..rank {
+        buf.extend((0..=col).map(|row| f(row, col)));
+    }
+    buf
 }
 
-/// A struct for writing the component strictly above the diagonal
-pub struct ColViewMut<RefType, BaseType, D: SquareDimension> {
-    ptr: *mut BaseType,
-    col_num: usize,
-    row: usize,
-    rank: D,
-    col_offset: usize,
-    _ref_type: PhantomData<RefType>,
+fn check_packed_len(len: usize, rank: usize) -> Result<(), UpperTriError> {
+    let expected = rank * (rank + 1) / 2;
+    if len != expected {
+        Err(UpperTriError::LengthMismatch {
+            expected,
+            found: len,
+        })
+    } else {
+        Ok(())
+    }
 }
 
-impl<'a, T: 'a, D: SquareDimension> Iterator for ColViewMut<&'a mut T, T, D> {
-    type Item = &'a mut T;
-    fn next(&mut self) -> Option<&'a mut T> {
-        if self.col_num >= self.rank.to_usize() || self.row >= self.col_num {
-            None
-        } else {
-            unsafe {
-                let offset = self.col_offset + self.row;
-                self.row += 1;
-                Some(&mut *self.ptr.add(offset))
-            }
+/// Offset of the logical element (i, j), mirrored into the upper triangle if needed
+#[cfg(feature = "alloc")]
+fn symmetric_offset(i: usize, j: usize) -> usize {
+    if i <= j {
+        offset_for_col(j, i)
+    } else {
+        offset_for_col(i, j)
+    }
+}
+
+/// Elements from consecutive columns of the packed buffer, where the gap to the element in the
+/// next column grows by one with each column. Rows, the diagonal and superdiagonals all have
+/// this shape. `rest` runs from the front element to the back element inclusive
+struct Gapped<S> {
+    rest: S,
+    col: usize,
+    end: usize,
+    delta: usize,
+}
+
+/// Where the elements (row, col) for col in `row + 1..rank` sit in the buffer, and the columns
+/// they span
+fn row_layout(rank: usize, row: usize) -> (Range<usize>, Range<usize>) {
+    if row >= rank.saturating_sub(1) {
+        return (0..0, 0..0);
+    }
+    (
+        offset_for_col(row + 1, row)..(offset_for_col(rank - 1, row) + 1),
+        (row + 1)..rank,
+    )
+}
+
+/// Where the elements (col - offset, col) for col in `offset..rank` sit in the buffer, and the
+/// columns they span
+fn superdiag_layout(rank: usize, offset: usize) -> (Range<usize>, Range<usize>) {
+    if offset >= rank {
+        return (0..0, 0..0);
+    }
+    (
+        offset_for_col(offset, 0)..(offset_for_col(rank - 1, rank - 1 - offset) + 1),
+        offset..rank,
+    )
+}
+
+impl<S> Gapped<S> {
+    fn new(rest: S, cols: Range<usize>, delta: usize) -> Self {
+        Gapped {
+            rest,
+            col: cols.start,
+            end: cols.end,
+            delta,
         }
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = 1 + self.col_num - self.row;
-        (remaining, Some(remaining))
+    /// The number of elements between the element in `col` and the one in `col + 1`
+    fn gap(&self, col: usize) -> usize {
+        col + self.delta
+    }
+
+    fn len(&self) -> usize {
+        self.end - self.col
     }
 }
 
-impl<'a, T: 'a, D: SquareDimension> Iterator for ColView<&'a T, T, D> {
-    type Item = &'a T;
+impl<'a, T> Gapped<&'a [T]> {
     fn next(&mut self) -> Option<&'a T> {
-        if self.col_num >= self.rank.to_usize() || self.row >= self.col_num {
-            None
-        } else {
-            unsafe {
-                let offset = self.col_offset + self.row;
-                self.row += 1;
-                Some(&*self.ptr.add(offset))
+        if self.col >= self.end {
+            return None;
+        }
+        let (first, tail) = self.rest.split_first()?;
+        let gap = self.gap(self.col).min(tail.len());
+        self.rest = &tail[gap..];
+        self.col += 1;
+        Some(first)
+    }
+
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.col >= self.end {
+            return None;
+        }
+        let (last, init) = self.rest.split_last()?;
+        self.end -= 1;
+        let gap = self.gap(self.end.saturating_sub(1)).min(init.len());
+        self.rest = &init[..(init.len() - gap)];
+        Some(last)
+    }
+}
+
+impl<'a, T> Gapped<&'a mut [T]> {
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.col >= self.end {
+            return None;
+        }
+        let (first, tail) = core::mem::take(&mut self.rest).split_first_mut()?;
+        let gap = self.gap(self.col).min(tail.len());
+        self.rest = &mut tail[gap..];
+        self.col += 1;
+        Some(first)
+    }
+
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.col >= self.end {
+            return None;
+        }
+        let (last, init) = core::mem::take(&mut self.rest).split_last_mut()?;
+        self.end -= 1;
+        let gap = self.gap(self.end.saturating_sub(1)).min(init.len());
+        let keep = init.len() - gap;
+        self.rest = &mut init[..keep];
+        Some(last)
+    }
+}
+
+/// The part of column `col` strictly above the diagonal, which is contiguous in the buffer
+fn col_range(rank: usize, col: usize) -> Range<usize> {
+    if col >= rank {
+        return 0..0;
+    }
+    let col_offset = offset_for_col(col, 0);
+    col_offset..(col_offset + col)
+}
+
+/// Forward `Iterator`, `DoubleEndedIterator`, `ExactSizeIterator` and `FusedIterator` to the
+/// wrapped iterator
+macro_rules! view_iterator {
+    ($view:ident, $item:ty) => {
+        impl<'a, T> Iterator for $view<'a, T> {
+            type Item = $item;
+            fn next(&mut self) -> Option<$item> {
+                self.0.next()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let remaining = self.0.len();
+                (remaining, Some(remaining))
+            }
+        }
+
+        impl<'a, T> DoubleEndedIterator for $view<'a, T> {
+            fn next_back(&mut self) -> Option<$item> {
+                self.0.next_back()
             }
         }
+
+        impl<'a, T> ExactSizeIterator for $view<'a, T> {}
+
+        impl<'a, T> FusedIterator for $view<'a, T> {}
+    };
+}
+
+/// A struct for reading the component strictly above the diagonal
+pub struct ColView<'a, T>(Iter<'a, T>);
+
+/// A struct for writing the component strictly above the diagonal
+pub struct ColViewMut<'a, T>(IterMut<'a, T>);
+
+impl<'a, T> ColView<'a, T> {
+    pub(crate) fn new(buf: &'a [T], rank: usize, col: usize) -> Self {
+        ColView(buf[col_range(rank, col)].iter())
     }
+}
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = 1 + self.col_num - self.row;
-        (remaining, Some(remaining))
+impl<'a, T> ColViewMut<'a, T> {
+    pub(crate) fn new(buf: &'a mut [T], rank: usize, col: usize) -> Self {
+        ColViewMut(buf[col_range(rank, col)].iter_mut())
     }
 }
 
-/// A struct for reading the component strictly to the left of the diagonal
-pub struct RowView<RefType, BaseType, D: SquareDimension> {
-    ptr: *const BaseType,
-    row_num: usize,
-    rank: D,
-    col: usize,
-    _ref_type: PhantomData<RefType>,
+view_iterator!(ColView, &'a T);
+view_iterator!(ColViewMut, &'a mut T);
+
+/// A struct for reading the component strictly to the right of the diagonal
+pub struct RowView<'a, T>(Gapped<&'a [T]>);
+
+/// A struct for writing the component strictly to the right of the diagonal
+pub struct RowViewMut<'a, T>(Gapped<&'a mut [T]>);
+
+impl<'a, T> RowView<'a, T> {
+    pub(crate) fn new(buf: &'a [T], rank: usize, row: usize) -> Self {
+        let (range, cols) = row_layout(rank, row);
+        RowView(Gapped::new(&buf[range], cols, 0))
+    }
 }
 
-/// A struct for writing the component strictly to the left of the diagonal
-pub struct RowViewMut<RefType, BaseType, D: SquareDimension> {
-    ptr: *mut BaseType,
-    row_num: usize,
-    col: usize,
-    rank: D,
-    _ref_type: PhantomData<RefType>,
+impl<'a, T> RowViewMut<'a, T> {
+    pub(crate) fn new(buf: &'a mut [T], rank: usize, row: usize) -> Self {
+        let (range, cols) = row_layout(rank, row);
+        RowViewMut(Gapped::new(&mut buf[range], cols, 0))
+    }
 }
 
-impl<'a, T: 'a, D: SquareDimension> Iterator for RowView<&'a T, T, D> {
-    type Item = &'a T;
-    fn next(&mut self) -> Option<&'a T> {
-        if self.col >= self.rank.to_usize() {
-            None
+view_iterator!(RowView, &'a T);
+view_iterator!(RowViewMut, &'a mut T);
+
+/// A struct for reading the column above a diagonal element, the element itself, and then the
+/// row to its right
+pub struct CornerView<'a, T> {
+    col: Iter<'a, T>,
+    row: RowView<'a, T>,
+}
+
+/// A struct for writing the column above a diagonal element, the element itself, and then the
+/// row to its right
+pub struct CornerViewMut<'a, T> {
+    col: IterMut<'a, T>,
+    row: RowViewMut<'a, T>,
+}
+
+impl<'a, T> CornerView<'a, T> {
+    pub(crate) fn new(buf: &'a [T], rank: usize, diagonal_element: usize) -> Self {
+        let col = if diagonal_element < rank {
+            let col_offset = offset_for_col(diagonal_element, 0);
+            &buf[col_offset..=(col_offset + diagonal_element)]
         } else {
-            unsafe {
-                let offset = offset_for_col(self.col, self.row_num);
-                self.col += 1;
-                Some(&*self.ptr.add(offset))
-            }
+            &[]
+        };
+        CornerView {
+            col: col.iter(),
+            row: RowView::new(buf, rank, diagonal_element),
+        }
+    }
+}
+
+impl<'a, T> CornerViewMut<'a, T> {
+    pub(crate) fn new(buf: &'a mut [T], rank: usize, diagonal_element: usize) -> Self {
+        if diagonal_element >= rank {
+            return CornerViewMut {
+                col: [].iter_mut(),
+                row: RowViewMut(Gapped::new(&mut [], 0..0, 0)),
+            };
+        }
+        // the row only touches later columns, so splitting the buffer after the diagonal element
+        // gives the two parts disjoint slices
+        let col_offset = offset_for_col(diagonal_element, 0);
+        let split = col_offset + diagonal_element + 1;
+        let (head, tail) = buf.split_at_mut(split);
+        let (range, cols) = row_layout(rank, diagonal_element);
+        let range = range.start.saturating_sub(split)..range.end.saturating_sub(split);
+        CornerViewMut {
+            col: head[col_offset..].iter_mut(),
+            row: RowViewMut(Gapped::new(&mut tail[range], cols, 0)),
         }
     }
+}
+
+impl<'a, T> Iterator for CornerView<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        self.col.next().or_else(|| self.row.next())
+    }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.rank.to_usize() - self.col;
+        let remaining = self.col.len() + self.row.len();
         (remaining, Some(remaining))
     }
 }
 
-impl<'a, T: 'a, D: SquareDimension> Iterator for RowViewMut<&'a mut T, T, D> {
+impl<'a, T> DoubleEndedIterator for CornerView<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.row.next_back().or_else(|| self.col.next_back())
+    }
+}
+
+impl<'a, T> ExactSizeIterator for CornerView<'a, T> {}
+
+impl<'a, T> FusedIterator for CornerView<'a, T> {}
+
+impl<'a, T> Iterator for CornerViewMut<'a, T> {
     type Item = &'a mut T;
     fn next(&mut self) -> Option<&'a mut T> {
-        if self.col >= self.rank.to_usize() {
-            None
-        } else {
-            unsafe {
-                let offset = offset_for_col(self.col, self.row_num);
-                self.col += 1;
-                Some(&mut *self.ptr.add(offset))
-            }
-        }
+        self.col.next().or_else(|| self.row.next())
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.rank.to_usize() - self.col;
+        let remaining = self.col.len() + self.row.len();
         (remaining, Some(remaining))
     }
 }
 
-pub struct CornerView<RefType, BaseType, D: SquareDimension> {
-    ptr: *const BaseType,
-    diagonal_element: usize,
-    pos: usize,
-    rank: D,
-    col_offset: usize,
-    _ref_type: PhantomData<RefType>,
+impl<'a, T> DoubleEndedIterator for CornerViewMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        self.row.next_back().or_else(|| self.col.next_back())
+    }
 }
 
-pub struct CornerViewMut<RefType, BaseType, D: SquareDimension> {
-    ptr: *mut BaseType,
-    diagonal_element: usize,
-    pos: usize,
-    rank: D,
-    col_offset: usize,
-    _ref_type: PhantomData<RefType>,
+impl<'a, T> ExactSizeIterator for CornerViewMut<'a, T> {}
+
+impl<'a, T> FusedIterator for CornerViewMut<'a, T> {}
+
+/// A struct for reading a full logical row of a skew symmetric matrix. Entries left of the
+/// diagonal are read from the column above it and negated
+pub struct SkewRowView<'a, T> {
+    col: Iter<'a, T>,
+    diag: Option<&'a T>,
+    row: RowView<'a, T>,
 }
 
-impl<'a, T: 'a, D: SquareDimension> Iterator for CornerViewMut<&'a mut T, T, D> {
-    type Item = &'a mut T;
-    fn next(&mut self) -> Option<&'a mut T> {
-        if self.diagonal_element >= self.rank.to_usize() {
-            None
-        } else if self.pos <= self.diagonal_element {
-            unsafe {
-                let offset = self.col_offset + self.pos;
-                self.pos += 1;
-                Some(&mut *self.ptr.add(offset))
-            }
-        } else if self.pos < self.rank.to_usize() {
-            unsafe {
-                let col = self.pos;
-                let row = self.diagonal_element;
-                let offset = offset_for_col(col, row);
-                self.pos += 1;
-                Some(&mut *self.ptr.add(offset))
-            }
+impl<'a, T> SkewRowView<'a, T> {
+    pub(crate) fn new(buf: &'a [T], rank: usize, row: usize) -> Self {
+        let diag = if row < rank {
+            buf.get(offset_for_col(row, row))
         } else {
             None
+        };
+        SkewRowView {
+            col: ColView::new(buf, rank, row).0,
+            diag,
+            row: RowView::new(buf, rank, row),
+        }
+    }
+}
+
+impl<'a, T: Neg<Output = T> + Clone> Iterator for SkewRowView<'a, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        if let Some(t) = self.col.next() {
+            return Some(-t.clone());
         }
+        self.diag.take().or_else(|| self.row.next()).cloned()
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.rank.to_usize() - self.pos;
+        let remaining = self.col.len() + self.diag.iter().len() + self.row.len();
         (remaining, Some(remaining))
     }
 }
 
-impl<'a, T: 'a, D: SquareDimension> Iterator for CornerView<&'a T, T, D> {
-    type Item = &'a T;
-    fn next(&mut self) -> Option<&'a T> {
-        if self.diagonal_element >= self.rank.to_usize() {
-            None
-        } else if self.pos <= self.diagonal_element {
-            unsafe {
-                let offset = self.col_offset + self.pos;
-                self.pos += 1;
-                Some(&*self.ptr.add(offset))
-            }
-        } else if self.pos < self.rank.to_usize() {
-            unsafe {
-                let col = self.pos;
-                let row = self.diagonal_element;
-                let offset = offset_for_col(col, row);
-                self.pos += 1;
-                Some(&*self.ptr.add(offset))
-            }
-        } else {
-            None
+impl<'a, T: Neg<Output = T> + Clone> DoubleEndedIterator for SkewRowView<'a, T> {
+    fn next_back(&mut self) -> Option<T> {
+        if let Some(t) = self.row.next_back().or_else(|| self.diag.take()) {
+            return Some(t.clone());
+        }
+        self.col.next_back().map(|t| -t.clone())
+    }
+}
+
+impl<'a, T: Neg<Output = T> + Clone> ExactSizeIterator for SkewRowView<'a, T> {}
+
+impl<'a, T: Neg<Output = T> + Clone> FusedIterator for SkewRowView<'a, T> {}
+
+/// A struct for reading the diagonal
+pub struct DiagView<'a, T>(Gapped<&'a [T]>);
+
+/// A struct for writing the diagonal
+pub struct DiagViewMut<'a, T>(Gapped<&'a mut [T]>);
+
+impl<'a, T> DiagView<'a, T> {
+    pub(crate) fn new(buf: &'a [T], rank: usize) -> Self {
+        let (range, cols) = superdiag_layout(rank, 0);
+        DiagView(Gapped::new(&buf[range], cols, 1))
+    }
+}
+
+impl<'a, T> DiagViewMut<'a, T> {
+    pub(crate) fn new(buf: &'a mut [T], rank: usize) -> Self {
+        let (range, cols) = superdiag_layout(rank, 0);
+        DiagViewMut(Gapped::new(&mut buf[range], cols, 1))
+    }
+}
+
+view_iterator!(DiagView, &'a T);
+view_iterator!(DiagViewMut, &'a mut T);
+
+/// A struct for reading the k-th superdiagonal, the elements (i, i + k)
+pub struct SuperDiagView<'a, T>(Gapped<&'a [T]>);
+
+/// A struct for writing the k-th superdiagonal, the elements (i, i + k)
+pub struct SuperDiagViewMut<'a, T>(Gapped<&'a mut [T]>);
+
+impl<'a, T> SuperDiagView<'a, T> {
+    pub(crate) fn new(buf: &'a [T], rank: usize, offset: usize) -> Self {
+        let (range, cols) = superdiag_layout(rank, offset);
+        SuperDiagView(Gapped::new(&buf[range], cols, 1))
+    }
+}
+
+impl<'a, T> SuperDiagViewMut<'a, T> {
+    pub(crate) fn new(buf: &'a mut [T], rank: usize, offset: usize) -> Self {
+        let (range, cols) = superdiag_layout(rank, offset);
+        SuperDiagViewMut(Gapped::new(&mut buf[range], cols, 1))
+    }
+}
+
+view_iterator!(SuperDiagView, &'a T);
+view_iterator!(SuperDiagViewMut, &'a mut T);
+
+/// The rows of column `col` down to and including the diagonal
+fn col_slice_range(rank: usize, col: usize) -> Range<usize> {
+    if col >= rank {
+        return 0..0;
+    }
+    let col_offset = offset_for_col(col, 0);
+    col_offset..(col_offset + col + 1)
+}
+
+/// Yields each column, down to and including the diagonal, as a contiguous slice
+pub struct Columns<'a, T> {
+    rest: &'a [T],
+    col: usize,
+    end: usize,
+}
+
+/// Yields each column, down to and including the diagonal, as a disjoint mutable slice
+pub struct ColumnsMut<'a, T> {
+    rest: &'a mut [T],
+    col: usize,
+    end: usize,
+}
+
+impl<'a, T> Columns<'a, T> {
+    pub(crate) fn new(buf: &'a [T], rank: usize) -> Self {
+        Columns {
+            rest: &buf[..offset_for_col(rank, 0)],
+            col: 0,
+            end: rank,
         }
     }
+}
+
+impl<'a, T> ColumnsMut<'a, T> {
+    pub(crate) fn new(buf: &'a mut [T], rank: usize) -> Self {
+        ColumnsMut {
+            rest: &mut buf[..offset_for_col(rank, 0)],
+            col: 0,
+            end: rank,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Columns<'a, T> {
+    type Item = &'a [T];
+    fn next(&mut self) -> Option<&'a [T]> {
+        if self.col >= self.end {
+            return None;
+        }
+        let (column, rest) = self.rest.split_at(self.col + 1);
+        self.rest = rest;
+        self.col += 1;
+        Some(column)
+    }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.rank.to_usize() - self.pos;
+        let remaining = self.end - self.col;
         (remaining, Some(remaining))
     }
 }
 
-pub struct DiagView<RefType, BaseType, D: SquareDimension> {
-    ptr: *const BaseType,
-    pos: usize,
-    rank: D,
-    _ref_type: PhantomData<RefType>,
+impl<'a, T> DoubleEndedIterator for Columns<'a, T> {
+    fn next_back(&mut self) -> Option<&'a [T]> {
+        if self.col >= self.end {
+            return None;
+        }
+        let (rest, column) = self.rest.split_at(self.rest.len() - self.end);
+        self.rest = rest;
+        self.end -= 1;
+        Some(column)
+    }
 }
 
-pub struct DiagViewMut<RefType, BaseType, D: SquareDimension> {
-    ptr: *mut BaseType,
-    pos: usize,
-    rank: D,
-    _ref_type: PhantomData<RefType>,
-}
+impl<'a, T> ExactSizeIterator for Columns<'a, T> {}
 
-impl<'a, T: 'a, D: SquareDimension> Iterator for DiagView<&'a T, T, D> {
-    type Item = &'a T;
-    fn next(&mut self) -> Option<&'a T> {
-        if self.pos >= self.rank.to_usize() {
-            None
-        } else if self.pos <= self.rank.to_usize() {
-            unsafe {
-                let offset = offset_for_col(self.pos, self.pos);
-                self.pos += 1;
-                Some(&*self.ptr.add(offset))
-            }
-        } else {
-            None
+impl<'a, T> FusedIterator for Columns<'a, T> {}
+
+impl<'a, T> Iterator for ColumnsMut<'a, T> {
+    type Item = &'a mut [T];
+    fn next(&mut self) -> Option<&'a mut [T]> {
+        if self.col >= self.end {
+            return None;
         }
+        let (column, rest) = core::mem::take(&mut self.rest).split_at_mut(self.col + 1);
+        self.rest = rest;
+        self.col += 1;
+        Some(column)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.rank.to_usize() - self.pos;
+        let remaining = self.end - self.col;
         (remaining, Some(remaining))
     }
 }
 
-impl<'a, T: 'a, D: SquareDimension> Iterator for DiagViewMut<&'a mut T, T, D> {
-    type Item = &'a mut T;
-    fn next(&mut self) -> Option<&'a mut T> {
-        if self.pos >= self.rank.to_usize() {
-            None
-        } else if self.pos <= self.rank.to_usize() {
-            unsafe {
-                let offset = offset_for_col(self.pos, self.pos);
-                self.pos += 1;
-                Some(&mut *self.ptr.add(offset))
+impl<'a, T> DoubleEndedIterator for ColumnsMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut [T]> {
+        if self.col >= self.end {
+            return None;
+        }
+        let rest = core::mem::take(&mut self.rest);
+        let split = rest.len() - self.end;
+        let (rest, column) = rest.split_at_mut(split);
+        self.rest = rest;
+        self.end -= 1;
+        Some(column)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for ColumnsMut<'a, T> {}
+
+impl<'a, T> FusedIterator for ColumnsMut<'a, T> {}
+
+/// Pairs each element of a packed order iterator with its position, optionally skipping the
+/// diagonal. The position is advanced as we go rather than recomputed from the offset
+pub struct IndexedIter<I> {
+    iter: I,
+    next: IndexPair,
+    rank: usize,
+    strict: bool,
+}
+
+impl<I> IndexedIter<I> {
+    pub(crate) fn new(iter: I, rank: usize, strict: bool) -> Self {
+        IndexedIter {
+            iter,
+            next: IndexPair { row: 0, col: 0 },
+            rank,
+            strict,
+        }
+    }
+}
+
+impl<I: ExactSizeIterator> Iterator for IndexedIter<I> {
+    type Item = (IndexPair, I::Item);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let t = self.iter.next()?;
+            let index_pair = self.next;
+            let IndexPair { row, col } = &mut self.next;
+            if *row >= *col {
+                *row = 0;
+                *col += 1;
+            } else {
+                *row += 1;
+            }
+            if !(self.strict && index_pair.row == index_pair.col) {
+                return Some((index_pair, t));
             }
-        } else {
-            None
         }
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.rank.to_usize() - self.pos;
+        let mut remaining = self.iter.len();
+        if self.strict {
+            remaining -= self.rank.saturating_sub(self.next.col).min(remaining);
+        }
         (remaining, Some(remaining))
     }
 }
 
-impl<T, D: SquareDimension> UpperTriRawData<T, D>
-where
-    T: Copy + Zero,
-{
-    fn data_size(&self) -> usize {
-        let rank = self.rank.to_usize();
-        rank * (rank + 1) / 2
-    }
+impl<I: ExactSizeIterator> ExactSizeIterator for IndexedIter<I> {}
 
-    fn get_offset(&self, row: usize, col: usize) -> Option<usize> {
-        let rank = self.rank.to_usize();
-        if row > col || col >= rank || row >= rank {
+impl<I: ExactSizeIterator + FusedIterator> FusedIterator for IndexedIter<I> {}
+
+/// Walks the whole triangle one superdiagonal at a time, starting with the main diagonal. This
+/// is increasing span order, so (i, j) comes after every (i, k) and (k, j) with i <= k <= j
+/// other than itself
+pub struct SuperDiagOrder<'a, T, D: SquareDimension> {
+    view: UpperTriView<'a, T, D>,
+    row: usize,
+    offset: usize,
+}
+
+impl<'a, T, D: SquareDimension> Iterator for SuperDiagOrder<'a, T, D> {
+    type Item = (IndexPair, &'a T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let rank = self.view.rank.to_usize();
+        if self.offset >= rank {
             return None;
+        }
+        let index_pair = IndexPair {
+            row: self.row,
+            col: self.row + self.offset,
+        };
+        let t = self.view.get(index_pair.row, index_pair.col)?;
+        self.row += 1;
+        if self.row + self.offset >= rank {
+            self.row = 0;
+            self.offset += 1;
+        }
+        Some((index_pair, t))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let rank = self.view.rank.to_usize();
+        let remaining = if self.offset >= rank {
+            0
         } else {
-            return Some(offset_for_col(col, row));
+            let later = rank - self.offset - 1;
+            (rank - self.offset - self.row) + later * (later + 1) / 2
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T, D: SquareDimension> ExactSizeIterator for SuperDiagOrder<'a, T, D> {}
+
+impl<T, D: SquareDimension, S> UpperTriRawData<T, D, S> {
+    const fn from_parts(buf: S, rank: D) -> Self {
+        UpperTriRawData {
+            buf,
+            rank,
+            marker: PhantomData,
         }
     }
 
-    pub fn get_diag_el(&self, index: usize) -> Option<&T> {
-        self.get(index, index)
+    /// The storage holding the packed elements
+    pub fn storage(&self) -> &S {
+        &self.buf
     }
 
-    pub fn get_diag_el_mut(&mut self, index: usize) -> Option<&mut T> {
-        self.get_mut(index, index)
+    pub fn into_storage(self) -> S {
+        self.buf
+    }
+}
+
+impl<T, S: PackedStorage<T>> UpperTriRawData<T, DynSquare, S> {
+    /// Wrap column packed storage, which must hold rank * (rank + 1) / 2 elements
+    pub fn from_packed(rank: usize, buf: S) -> Result<Self, UpperTriError> {
+        check_packed_len(buf.as_slice().len(), rank)?;
+        Ok(Self::from_parts(buf, DynSquare(rank)))
+    }
+}
+
+impl<T, D: StaticDimension, S: PackedStorage<T>> UpperTriRawData<T, D, S> {
+    /// Wrap column packed storage, which must hold RANK * (RANK + 1) / 2 elements
+    pub fn from_packed(buf: S) -> Result<Self, UpperTriError> {
+        check_packed_len(buf.as_slice().len(), D::RANK)?;
+        Ok(Self::from_parts(buf, D::DIM))
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorage<T>> UpperTriRawData<T, D, S> {
+    /// The packed elements in column order
+    pub fn as_slice(&self) -> &[T] {
+        self.buf.as_slice()
+    }
+
+    pub fn get_diag_el(&self, index: usize) -> Option<&T> {
+        self.get(index, index)
     }
 
     pub fn get(&self, row: usize, col: usize) -> Option<&T> {
-        let offset = self.get_offset(row, col)?;
-        self.buf.get(offset)
+        self.as_view().get(row, col)
     }
 
-    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
-        let offset = self.get_offset(row, col)?;
-        self.buf.get_mut(offset)
+    pub fn get_raw_col(&self, col: usize) -> ColView<'_, T> {
+        self.as_view().get_raw_col(col)
     }
 
-    pub fn get_raw_col<'a>(&'a self, col: usize) -> ColView<&'a T, T, D> {
-        ColView {
-            ptr: self.buf.as_ptr(),
-            col_num: col,
-            row: 0,
-            rank: self.rank,
-            col_offset: col * (col + 1) / 2,
-            _ref_type: PhantomData,
-        }
+    pub fn get_raw_row(&self, row: usize) -> RowView<'_, T> {
+        self.as_view().get_raw_row(row)
     }
 
-    pub fn get_raw_col_mut<'a>(&'a mut self, col: usize) -> ColViewMut<&'a mut T, T, D> {
-        ColViewMut {
-            ptr: self.buf.as_mut_ptr(),
-            col_num: col,
-            row: 0,
-            rank: self.rank,
-            _ref_type: PhantomData,
-            col_offset: col * (col + 1) / 2,
-        }
+    pub fn get_corner(&self, diagonal_element: usize) -> CornerView<'_, T> {
+        self.as_view().get_corner(diagonal_element)
     }
 
-    pub fn get_raw_row<'a>(&'a self, row: usize) -> RowView<&'a T, T, D> {
-        RowView {
-            ptr: self.buf.as_ptr(),
-            row_num: row,
-            col: row + 1,
-            rank: self.rank,
-            _ref_type: PhantomData,
-        }
+    pub fn get_diag(&self) -> DiagView<'_, T> {
+        self.as_view().get_diag()
     }
 
-    pub fn get_raw_row_mut<'a>(&'a mut self, row: usize) -> RowViewMut<&'a mut T, T, D> {
-        RowViewMut {
-            ptr: self.buf.as_mut_ptr(),
-            row_num: row,
-            col: row + 1,
-            rank: self.rank,
-            _ref_type: PhantomData,
-        }
+    /// All entries of logical row `row` when the matrix is read as symmetric
+    pub fn sym_row(&self, row: usize) -> CornerView<'_, T> {
+        self.as_view().sym_row(row)
     }
 
-    pub fn get_corner<'a>(&'a self, diagonal_element: usize) -> CornerView<&'a T, T, D> {
-        CornerView {
-            ptr: self.buf.as_ptr(),
-            diagonal_element,
-            pos: 0,
-            rank: self.rank,
-            col_offset: diagonal_element * (diagonal_element + 1) / 2,
-            _ref_type: PhantomData,
-        }
+    /// All entries of logical row `row` when the matrix is read as skew symmetric
+    pub fn skew_row(&self, row: usize) -> SkewRowView<'_, T> {
+        self.as_view().skew_row(row)
     }
 
-    pub fn get_corner_mut<'a>(
-        &'a mut self,
-        diagonal_element: usize,
-    ) -> CornerViewMut<&'a mut T, T, D> {
-        CornerViewMut {
-            ptr: self.buf.as_mut_ptr(),
-            diagonal_element,
-            pos: 0,
-            rank: self.rank,
-            col_offset: diagonal_element * (diagonal_element + 1) / 2,
-            _ref_type: PhantomData,
-        }
+    pub fn get_superdiag(&self, offset: usize) -> SuperDiagView<'_, T> {
+        self.as_view().get_superdiag(offset)
     }
 
-    pub fn get_diag(&self) -> DiagView<&T, T, D> {
-        DiagView {
-            ptr: self.buf.as_ptr(),
-            pos: 0,
-            rank: self.rank,
-            _ref_type: PhantomData,
-        }
+    pub fn iter_by_superdiag(&self) -> SuperDiagOrder<'_, T, D> {
+        self.as_view().iter_by_superdiag()
     }
 
-    pub fn get_diag_mut(&mut self) -> DiagViewMut<&mut T, T, D> {
-        DiagViewMut {
-            ptr: self.buf.as_mut_ptr(),
-            pos: 0,
-            rank: self.rank,
-            _ref_type: PhantomData,
-        }
+    /// Column `col` down to and including the diagonal. Empty if `col` is out of range
+    pub fn col_slice(&self, col: usize) -> &[T] {
+        self.as_view().col_slice(col)
     }
 
-    pub fn map<B, F: FnMut(&T) -> B>(&self, f: F) -> UpperTriRawData<B, D> {
-        let buf = self.buf.iter().map(f).collect();
-        UpperTriRawData {
-            buf,
-            rank: self.rank,
-        }
+    pub fn columns(&self) -> Columns<'_, T> {
+        self.as_view().columns()
     }
 
-    pub fn map_inplace<F: FnMut(&T) -> T>(&mut self, mut f: F) {
-        self.buf.iter_mut().for_each(|t| {
-            let new = f(t);
-            *t = new;
-        });
+    fn check_rank<U, M>(&self, other: &M) -> Result<(), UpperTriError>
+    where
+        M: TriangularMatrix<U, Dim = D> + ?Sized,
+    {
+        let expected = self.rank.to_usize();
+        let found = other.rank();
+        if expected != found {
+            Err(UpperTriError::RankMismatch { expected, found })
+        } else {
+            Ok(())
+        }
     }
 
     pub fn find_with_indices<Accum, Test, Accumulator>(
@@ -434,116 +866,388 @@ where
         Accumulator: Fn(&T, &Accum) -> Accum,
     {
         let mut accum: Accum = Accum::default();
-        let mut index_pair = IndexPair { row: 0, col: 0 };
-        let _ = self
-            .buf
-            .iter()
-            .map(|t| {
-                let b = accumulator(t, &accum);
-                accum = b.clone();
-                b
-            })
-            .find(|b| {
-                let test_val = test(b);
-                if !test_val {
-                    let IndexPair { row, col } = &mut index_pair;
-                    if *row >= *col {
-                        *row = 0;
-                        *col += 1;
-                    } else {
-                        *row += 1
-                    };
-                }
-                test_val
-            });
-
-        (index_pair, accum)
+        for (index_pair, t) in self.indexed_iter() {
+            accum = accumulator(t, &accum);
+            if test(&accum) {
+                return (index_pair, accum);
+            }
+        }
+        let end = IndexPair {
+            row: 0,
+            col: self.rank.to_usize(),
+        };
+        (end, accum)
     }
 
     pub fn iter(&self) -> Iter<'_, T> {
-        self.buf.iter()
+        self.as_slice().iter()
+    }
+
+    /// Iterate in packed order together with the position of each element
+    pub fn indexed_iter(&self) -> IndexedIter<Iter<'_, T>> {
+        self.as_view().indexed_iter()
+    }
+
+    /// Like `indexed_iter` but skipping the diagonal
+    pub fn strict_upper_iter(&self) -> IndexedIter<Iter<'_, T>> {
+        self.as_view().strict_upper_iter()
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorageMut<T>> UpperTriRawData<T, D, S> {
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.buf.as_mut_slice()
+    }
+
+    pub fn get_diag_el_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.get_mut(index, index)
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.as_view_mut().into_mut(row, col)
+    }
+
+    pub fn get_raw_col_mut(&mut self, col: usize) -> ColViewMut<'_, T> {
+        self.as_view_mut().into_raw_col_mut(col)
+    }
+
+    pub fn get_raw_row_mut(&mut self, row: usize) -> RowViewMut<'_, T> {
+        self.as_view_mut().into_raw_row_mut(row)
+    }
+
+    pub fn get_corner_mut(&mut self, diagonal_element: usize) -> CornerViewMut<'_, T> {
+        self.as_view_mut().into_corner_mut(diagonal_element)
+    }
+
+    pub fn sym_row_mut(&mut self, row: usize) -> CornerViewMut<'_, T> {
+        self.as_view_mut().into_corner_mut(row)
+    }
+
+    pub fn get_diag_mut(&mut self) -> DiagViewMut<'_, T> {
+        self.as_view_mut().into_diag_mut()
+    }
+
+    pub fn get_superdiag_mut(&mut self, offset: usize) -> SuperDiagViewMut<'_, T> {
+        self.as_view_mut().into_superdiag_mut(offset)
+    }
+
+    pub fn col_slice_mut(&mut self, col: usize) -> &mut [T] {
+        self.as_view_mut().into_col_slice_mut(col)
+    }
+
+    pub fn columns_mut(&mut self) -> ColumnsMut<'_, T> {
+        self.as_view_mut().into_columns_mut()
+    }
+
+    pub fn map_inplace<F: FnMut(&T) -> T>(&mut self, mut f: F) {
+        self.iter_mut().for_each(|t| {
+            let new = f(t);
+            *t = new;
+        });
+    }
+
+    /// Update every element in place from the matching element of `other`
+    pub fn zip_apply<U, M: TriangularMatrix<U, Dim = D>, F: FnMut(&mut T, &U)>(
+        &mut self,
+        other: &M,
+        mut f: F,
+    ) -> Result<(), UpperTriError> {
+        self.check_rank(other)?;
+        self.iter_mut().zip(other.iter()).for_each(|(t, u)| f(t, u));
+        Ok(())
+    }
+
+    /// Update every element in place from the matching elements of `second` and `third`
+    pub fn zip3_apply<U, V, M2, M3, F>(
+        &mut self,
+        second: &M2,
+        third: &M3,
+        mut f: F,
+    ) -> Result<(), UpperTriError>
+    where
+        M2: TriangularMatrix<U, Dim = D>,
+        M3: TriangularMatrix<V, Dim = D>,
+        F: FnMut(&mut T, &U, &V),
+    {
+        self.check_rank(second)?;
+        self.check_rank(third)?;
+        self.iter_mut()
+            .zip(second.iter())
+            .zip(third.iter())
+            .for_each(|((t, u), v)| f(t, u, v));
+        Ok(())
     }
 
     pub fn iter_mut(&mut self) -> IterMut<'_, T> {
-        self.buf.iter_mut()
+        self.as_mut_slice().iter_mut()
+    }
+
+    pub fn indexed_iter_mut(&mut self) -> IndexedIter<IterMut<'_, T>> {
+        let rank = self.rank.to_usize();
+        IndexedIter::new(self.iter_mut(), rank, false)
+    }
+
+    pub fn strict_upper_iter_mut(&mut self) -> IndexedIter<IterMut<'_, T>> {
+        let rank = self.rank.to_usize();
+        IndexedIter::new(self.iter_mut(), rank, true)
+    }
+}
+
+/// Operations that build a new matrix, which is always backed by a `Vec`
+#[cfg(feature = "alloc")]
+impl<T, D: SquareDimension, S: PackedStorage<T>> UpperTriRawData<T, D, S> {
+    pub fn map<B, F: FnMut(&T) -> B>(&self, f: F) -> UpperTriRawData<B, D> {
+        UpperTriRawData::from_parts(self.iter().map(f).collect(), self.rank)
+    }
+
+    /// Combine two matrices of the same rank elementwise into a new matrix
+    pub fn zip_map<U, M: TriangularMatrix<U, Dim = D>, B, F: FnMut(&T, &U) -> B>(
+        &self,
+        other: &M,
+        mut f: F,
+    ) -> Result<UpperTriRawData<B, D>, UpperTriError> {
+        self.check_rank(other)?;
+        let buf = self
+            .iter()
+            .zip(other.iter())
+            .map(|(t, u)| f(t, u))
+            .collect();
+        Ok(UpperTriRawData::from_parts(buf, self.rank))
+    }
+
+    /// Combine three matrices of the same rank elementwise into a new matrix
+    pub fn zip3_map<U, V, M2, M3, B, F>(
+        &self,
+        second: &M2,
+        third: &M3,
+        mut f: F,
+    ) -> Result<UpperTriRawData<B, D>, UpperTriError>
+    where
+        M2: TriangularMatrix<U, Dim = D>,
+        M3: TriangularMatrix<V, Dim = D>,
+        F: FnMut(&T, &U, &V) -> B,
+    {
+        self.check_rank(second)?;
+        self.check_rank(third)?;
+        let buf = self
+            .iter()
+            .zip(second.iter())
+            .zip(third.iter())
+            .map(|((t, u), v)| f(t, u, v))
+            .collect();
+        Ok(UpperTriRawData::from_parts(buf, self.rank))
+    }
+}
+
+/// Consuming iteration, for storage that hands out its elements by value
+impl<T, D: SquareDimension, S> UpperTriRawData<T, D, S>
+where
+    S: PackedStorage<T> + IntoIterator<Item = T>,
+{
+    pub fn into_indexed_iter(self) -> IndexedIter<S::IntoIter> {
+        let rank = self.rank.to_usize();
+        IndexedIter::new(self.buf.into_iter(), rank, false)
+    }
+
+    pub fn into_strict_upper_iter(self) -> IndexedIter<S::IntoIter> {
+        let rank = self.rank.to_usize();
+        IndexedIter::new(self.buf.into_iter(), rank, true)
     }
+}
+
+/// Panics if the position is out of range or below the diagonal, like `get` returning `None`
+impl<T, D: SquareDimension, S: PackedStorage<T>> Index<(usize, usize)>
+    for UpperTriRawData<T, D, S>
+{
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        let rank = self.rank.to_usize();
+        match self.as_view().get(row, col) {
+            Some(t) => t,
+            None => panic!("index ({}, {}) out of range for rank {}", row, col, rank),
+        }
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorageMut<T>> IndexMut<(usize, usize)>
+    for UpperTriRawData<T, D, S>
+{
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let rank = self.rank.to_usize();
+        match self.as_view_mut().into_mut(row, col) {
+            Some(t) => t,
+            None => panic!("index ({}, {}) out of range for rank {}", row, col, rank),
+        }
+    }
+}
 
-    pub fn into_iter(self) -> IntoIter<T> {
+impl<T, D: SquareDimension, S: PackedStorage<T>> Index<IndexPair> for UpperTriRawData<T, D, S> {
+    type Output = T;
+
+    fn index(&self, pair: IndexPair) -> &T {
+        &self[(pair.row, pair.col)]
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorageMut<T>> IndexMut<IndexPair>
+    for UpperTriRawData<T, D, S>
+{
+    fn index_mut(&mut self, pair: IndexPair) -> &mut T {
+        &mut self[(pair.row, pair.col)]
+    }
+}
+
+impl<T, D: SquareDimension, S> IntoIterator for UpperTriRawData<T, D, S>
+where
+    S: PackedStorage<T> + IntoIterator<Item = T>,
+{
+    type Item = T;
+    type IntoIter = S::IntoIter;
+
+    fn into_iter(self) -> S::IntoIter {
         self.buf.into_iter()
     }
 }
 
-impl<'a, T, D: SquareDimension> SubAssign<&'a UpperTriRawData<T, D>> for UpperTriRawData<T, D>
+/// Elementwise (Hadamard) product and quotient into a new matrix, so any readable storage works,
+/// including shared `Arc<[T]>` buffers
+#[cfg(feature = "alloc")]
+impl<T: Clone, D: SquareDimension, S: PackedStorage<T>> UpperTriRawData<T, D, S> {
+    pub fn hadamard<M: TriangularMatrix<T, Dim = D>>(
+        &self,
+        other: &M,
+    ) -> Result<UpperTriRawData<T, D>, UpperTriError>
+    where
+        T: Mul<Output = T>,
+    {
+        self.zip_map(other, |left, right| left.clone() * right.clone())
+    }
+
+    pub fn hadamard_div<M: TriangularMatrix<T, Dim = D>>(
+        &self,
+        other: &M,
+    ) -> Result<UpperTriRawData<T, D>, UpperTriError>
+    where
+        T: Div<Output = T>,
+    {
+        self.zip_map(other, |left, right| left.clone() / right.clone())
+    }
+}
+
+/// Elementwise (Hadamard) product in place
+impl<T, D: SquareDimension, S: PackedStorageMut<T>> UpperTriRawData<T, D, S>
+where
+    T: Clone + Mul<Output = T>,
+{
+    pub fn hadamard_inplace<M: TriangularMatrix<T, Dim = D>>(
+        &mut self,
+        other: &M,
+    ) -> Result<(), UpperTriError> {
+        self.zip_apply(other, |left, right| *left = left.clone() * right.clone())
+    }
+}
+
+/// Elementwise division in place
+impl<T, D: SquareDimension, S: PackedStorageMut<T>> UpperTriRawData<T, D, S>
 where
-    T: for<'b> SubAssign<&'b T> + Copy + Zero,
+    T: Clone + Div<Output = T>,
 {
-    fn sub_assign(&mut self, rhs: &'a UpperTriRawData<T, D>) {
+    pub fn hadamard_div_inplace<M: TriangularMatrix<T, Dim = D>>(
+        &mut self,
+        other: &M,
+    ) -> Result<(), UpperTriError> {
+        self.zip_apply(other, |left, right| *left = left.clone() / right.clone())
+    }
+}
+
+impl<'a, T, D, S, M> SubAssign<&'a M> for UpperTriRawData<T, D, S>
+where
+    T: for<'b> SubAssign<&'b T>,
+    D: SquareDimension,
+    S: PackedStorageMut<T>,
+    M: TriangularMatrix<T, Dim = D> + ?Sized,
+{
+    fn sub_assign(&mut self, rhs: &'a M) {
         self.iter_mut()
             .zip(rhs.iter())
             .for_each(|(left, right)| *left -= right);
     }
 }
 
-impl<T, D: SquareDimension> SubAssign<UpperTriRawData<T, D>> for UpperTriRawData<T, D>
+impl<T, D, S, S2> SubAssign<UpperTriRawData<T, D, S2>> for UpperTriRawData<T, D, S>
 where
-    T: SubAssign<T> + Copy + Zero,
+    T: SubAssign<T>,
+    D: SquareDimension,
+    S: PackedStorageMut<T>,
+    S2: PackedStorage<T> + IntoIterator<Item = T>,
 {
-    fn sub_assign(&mut self, rhs: UpperTriRawData<T, D>) {
+    fn sub_assign(&mut self, rhs: UpperTriRawData<T, D, S2>) {
         self.iter_mut()
-            .zip(rhs.into_iter())
+            .zip(rhs)
             .for_each(|(left, right)| *left -= right);
     }
 }
 
-impl<T: Display + Zero + Copy, D: SquareDimension> Display for UpperTriRawData<T, D> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        let space_len = format!("{} ", T::zero()).len();
-        let space = repeat(" ").take(space_len).collect::<String>();
-        write!(f, "\n")?;
-        (0..self.rank.to_usize())
-            .map(|row| -> Result<(), std::fmt::Error> {
-                let spaces = repeat(space.clone()).take(row).collect::<String>();
-                write!(f, "\t{}", spaces)?;
-                let diag = self.get_diag_el(row).ok_or(std::fmt::Error)?;
-                write!(f, "{} ", diag)?;
-                self.get_raw_row(row)
-                    .map(|t| -> Result<_, _> { write!(f, "{} ", t) })
-                    .collect::<Result<Vec<()>, _>>()?;
-                write!(f, "\n")
-            })
-            .collect::<Result<Vec<()>, std::fmt::Error>>()
-            .map(|_| ())
+/// Elements are padded to the width of the widest one so the columns line up
+impl<T: Display, D: SquareDimension, S: PackedStorage<T>> Display for UpperTriRawData<T, D, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::result::Result<(), core::fmt::Error> {
+        fmt_triangle(self, f)
     }
 }
 
-impl<'a, T, D: SquareDimension> AddAssign<&'a UpperTriRawData<T, D>> for UpperTriRawData<T, D>
+/// Elementwise sum in place. Only dynamic matrices can differ in rank, and then the two combine
+/// over their shared leading block, which is a prefix of both packed buffers, leaving the rest of
+/// `self` as it was. `-=`, the views and `add` follow the same rule; dynamic `&a + &b` keeps the
+/// larger of the two ranks
+impl<'a, T, D, S, M> AddAssign<&'a M> for UpperTriRawData<T, D, S>
 where
-    T: Copy + Zero + for<'b> AddAssign<&'b T>,
+    T: for<'b> AddAssign<&'b T>,
+    D: SquareDimension,
+    S: PackedStorageMut<T>,
+    M: TriangularMatrix<T, Dim = D> + ?Sized,
 {
-    fn add_assign(&mut self, rhs: &'a UpperTriRawData<T, D>) {
-        self.buf
-            .iter_mut()
-            .zip(rhs.buf.iter())
+    fn add_assign(&mut self, rhs: &'a M) {
+        self.iter_mut()
+            .zip(rhs.iter())
             .for_each(|(return_val, new)| {
                 *return_val += new;
             });
     }
 }
 
-impl<T, D: SquareDimension> AddAssign<UpperTriRawData<T, D>> for UpperTriRawData<T, D>
+impl<T, D, S, S2> AddAssign<UpperTriRawData<T, D, S2>> for UpperTriRawData<T, D, S>
 where
-    T: Copy + Zero + for<'b> AddAssign<&'b T>,
+    T: for<'b> AddAssign<&'b T>,
+    D: SquareDimension,
+    S: PackedStorageMut<T>,
+    S2: PackedStorage<T>,
 {
-    fn add_assign(&mut self, rhs: UpperTriRawData<T, D>) {
+    fn add_assign(&mut self, rhs: UpperTriRawData<T, D, S2>) {
         *self += &rhs;
     }
 }
 
+/// The elementwise sum of two matrices, owned or borrowed, into a new matrix with the rank of
+/// `left`
+#[cfg(feature = "alloc")]
+pub fn add<T, L, R>(left: &L, right: &R) -> UpperTriRawData<T, L::Dim>
+where
+    T: Clone + for<'b> AddAssign<&'b T>,
+    L: TriangularMatrix<T> + ?Sized,
+    R: TriangularMatrix<T, Dim = L::Dim> + ?Sized,
+{
+    let mut sum = UpperTriRawData::from_parts(left.as_slice().to_vec(), left.dim());
+    sum += right;
+    sum
+}
+
+#[cfg(feature = "alloc")]
 impl<'a, T, D: SquareDimension> Add<&'a UpperTriRawData<T, D>> for UpperTriRawData<T, D>
 where
     &'a UpperTriRawData<T, D>:
         for<'b> Add<&'b UpperTriRawData<T, D>, Output = UpperTriRawData<T, D>>,
-    T: Copy + Zero + for<'b> AddAssign<&'b T>,
+    T: for<'b> AddAssign<&'b T>,
 {
     type Output = UpperTriRawData<T, D>;
 
@@ -552,11 +1256,12 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, T, D: SquareDimension> Add<UpperTriRawData<T, D>> for &'a UpperTriRawData<T, D>
 where
     &'a UpperTriRawData<T, D>:
         for<'b> Add<&'b UpperTriRawData<T, D>, Output = UpperTriRawData<T, D>>,
-    T: Copy + Zero + for<'b> AddAssign<&'b T>,
+    T: for<'b> AddAssign<&'b T>,
 {
     type Output = UpperTriRawData<T, D>;
 
@@ -565,10 +1270,11 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<T, D: SquareDimension> Add<UpperTriRawData<T, D>> for UpperTriRawData<T, D>
 where
     UpperTriRawData<T, D>: for<'b> Add<&'b UpperTriRawData<T, D>, Output = UpperTriRawData<T, D>>,
-    T: Copy + Zero + for<'b> AddAssign<&'b T>,
+    T: for<'b> AddAssign<&'b T>,
 {
     type Output = UpperTriRawData<T, D>;
 
@@ -577,5 +1283,7 @@ where
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod test;
+#[cfg(all(test, not(feature = "alloc")))]
+mod test_core;
@@ -0,0 +1,91 @@
+use crate::upper_tri::*;
+
+fn check_permutation(perm: &[usize], rank: usize) -> Result<(), UpperTriError> {
+    let mut seen = vec![false; rank];
+    if perm.len() != rank {
+        return Err(UpperTriError::InvalidPermutation { rank });
+    }
+    for &p in perm {
+        if p >= rank || seen[p] {
+            return Err(UpperTriError::InvalidPermutation { rank });
+        }
+        seen[p] = true;
+    }
+    Ok(())
+}
+
+impl<T, D: SquareDimension, S: PackedStorageMut<T>> UpperTriRawData<T, D, S> {
+    /// Swap rows and columns `i` and `j` of the symmetric matrix
+    pub fn swap_indices(&mut self, i: usize, j: usize) {
+        let rank = self.rank.to_usize();
+        assert!(
+            i < rank && j < rank,
+            "swap indices ({}, {}) out of range for rank {}",
+            i,
+            j,
+            rank
+        );
+        if i == j {
+            return;
+        }
+        let buf = self.as_mut_slice();
+        buf.swap(offset_for_col(i, i), offset_for_col(j, j));
+        for k in (0..rank).filter(|k| *k != i && *k != j) {
+            buf.swap(symmetric_offset(k, i), symmetric_offset(k, j));
+        }
+    }
+
+    /// In place version of `permute_symmetric`, applying each cycle of the permutation as a
+    /// sequence of `swap_indices`
+    pub fn permute_symmetric_inplace(&mut self, perm: &[usize]) -> Result<(), UpperTriError> {
+        let rank = self.rank.to_usize();
+        check_permutation(perm, rank)?;
+        let mut visited = vec![false; rank];
+        for start in 0..rank {
+            if visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            let mut next = perm[start];
+            while next != start {
+                self.swap_indices(start, next);
+                visited[next] = true;
+                next = perm[next];
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compute P A Pᵀ, sending the logical element (i, j) to `(perm[i], perm[j])`
+pub fn permute_symmetric<T, M>(
+    matrix: &M,
+    perm: &[usize],
+) -> Result<UpperTriRawData<T, M::Dim>, UpperTriError>
+where
+    T: Clone,
+    M: TriangularMatrix<T> + ?Sized,
+{
+    let rank = matrix.rank();
+    check_permutation(perm, rank)?;
+    let mut inverse = vec![0; rank];
+    perm.iter().enumerate().for_each(|(i, p)| inverse[*p] = i);
+
+    let packed = matrix.as_slice();
+    let mut buf = Vec::with_capacity(packed.len());
+    for col in 0..rank {
+        buf.extend(
+            (0..=col).map(|row| packed[symmetric_offset(inverse[row], inverse[col])].clone()),
+        );
+    }
+    Ok(UpperTriRawData::from_parts(buf, matrix.dim()))
+}
+
+impl<T: Clone, D: SquareDimension, S: PackedStorage<T>> UpperTriRawData<T, D, S> {
+    pub fn permute_symmetric(
+        &self,
+        perm: &[usize],
+    ) -> Result<UpperTriRawData<T, D>, UpperTriError> {
+        permute_symmetric(self, perm)
+    }
+}
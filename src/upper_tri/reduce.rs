@@ -0,0 +1,202 @@
+use crate::upper_tri::*;
+#[cfg(feature = "alloc")]
+use alloc::collections::BinaryHeap;
+use core::cmp::Ordering;
+use core::cmp::Reverse;
+
+/// A value with its position, ordered by value and then by `tie`. Only built for values that are
+/// comparable with themselves, so the fallback to `Equal` is never reached for NaN
+struct Ranked<'a, T, P> {
+    value: &'a T,
+    tie: P,
+    pair: IndexPair,
+}
+
+impl<'a, T: PartialOrd, P: Ord> Ord for Ranked<'a, T, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value
+            .partial_cmp(other.value)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.tie.cmp(&other.tie))
+    }
+}
+
+impl<'a, T: PartialOrd, P: Ord> PartialOrd for Ranked<'a, T, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, T: PartialOrd, P: Ord> PartialEq for Ranked<'a, T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a, T: PartialOrd, P: Ord> Eq for Ranked<'a, T, P> {}
+
+/// Reductions over indexed elements. Values that are not comparable with themselves, such as
+/// NaN, are skipped, and ties go to the element that comes first in packed order. Call these on
+/// `strict_upper_iter` to leave out the diagonal
+impl<'a, T, I> IndexedIter<I>
+where
+    T: PartialOrd + 'a,
+    I: ExactSizeIterator<Item = &'a T>,
+{
+    fn comparable(self) -> impl Iterator<Item = (usize, IndexPair, &'a T)> {
+        self.enumerate()
+            .filter(|(_, (_, t))| t.partial_cmp(t).is_some())
+            .map(|(position, (pair, t))| (position, pair, t))
+    }
+
+    pub fn argmin(self) -> Option<(IndexPair, &'a T)> {
+        self.comparable()
+            .map(|(position, pair, value)| Ranked {
+                value,
+                tie: position,
+                pair,
+            })
+            .min()
+            .map(|ranked| (ranked.pair, ranked.value))
+    }
+
+    pub fn argmax(self) -> Option<(IndexPair, &'a T)> {
+        self.comparable()
+            .map(|(position, pair, value)| Ranked {
+                value,
+                tie: Reverse(position),
+                pair,
+            })
+            .max()
+            .map(|ranked| (ranked.pair, ranked.value))
+    }
+}
+
+/// Reductions that collect their result, keeping the same order and NaN handling as above
+#[cfg(feature = "alloc")]
+impl<'a, T, I> IndexedIter<I>
+where
+    T: PartialOrd + 'a,
+    I: ExactSizeIterator<Item = &'a T>,
+{
+    /// The `k` largest elements, largest first, keeping at most `k` of them at a time
+    pub fn top_k(self, k: usize) -> Vec<(IndexPair, &'a T)> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        for (position, pair, value) in self.comparable() {
+            heap.push(Reverse(Ranked {
+                value,
+                tie: Reverse(position),
+                pair,
+            }));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(ranked)| (ranked.pair, ranked.value))
+            .collect()
+    }
+
+    /// The `k` smallest elements, smallest first, keeping at most `k` of them at a time
+    pub fn bottom_k(self, k: usize) -> Vec<(IndexPair, &'a T)> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        for (position, pair, value) in self.comparable() {
+            heap.push(Ranked {
+                value,
+                tie: position,
+                pair,
+            });
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|ranked| (ranked.pair, ranked.value))
+            .collect()
+    }
+
+    /// Every element in increasing order
+    pub fn sorted_pairs(self) -> Vec<(IndexPair, &'a T)> {
+        let mut pairs = self.comparable().collect::<Vec<_>>();
+        pairs.sort_by(|(_, _, left), (_, _, right)| {
+            left.partial_cmp(right).unwrap_or(Ordering::Equal)
+        });
+        pairs
+            .into_iter()
+            .map(|(_, pair, value)| (pair, value))
+            .collect()
+    }
+}
+
+/// The smallest element over every element including the diagonal. Use `strict_upper_iter` for
+/// the version without it
+pub fn argmin<T, M>(matrix: &M) -> Option<(IndexPair, &T)>
+where
+    T: PartialOrd,
+    M: TriangularMatrix<T> + ?Sized,
+{
+    matrix.indexed_iter().argmin()
+}
+
+/// The largest element over every element including the diagonal
+pub fn argmax<T, M>(matrix: &M) -> Option<(IndexPair, &T)>
+where
+    T: PartialOrd,
+    M: TriangularMatrix<T> + ?Sized,
+{
+    matrix.indexed_iter().argmax()
+}
+
+#[cfg(feature = "alloc")]
+pub fn top_k<T, M>(matrix: &M, k: usize) -> Vec<(IndexPair, &T)>
+where
+    T: PartialOrd,
+    M: TriangularMatrix<T> + ?Sized,
+{
+    matrix.indexed_iter().top_k(k)
+}
+
+#[cfg(feature = "alloc")]
+pub fn bottom_k<T, M>(matrix: &M, k: usize) -> Vec<(IndexPair, &T)>
+where
+    T: PartialOrd,
+    M: TriangularMatrix<T> + ?Sized,
+{
+    matrix.indexed_iter().bottom_k(k)
+}
+
+#[cfg(feature = "alloc")]
+pub fn sorted_pairs<T, M>(matrix: &M) -> Vec<(IndexPair, &T)>
+where
+    T: PartialOrd,
+    M: TriangularMatrix<T> + ?Sized,
+{
+    matrix.indexed_iter().sorted_pairs()
+}
+
+impl<T: PartialOrd, D: SquareDimension, S: PackedStorage<T>> UpperTriRawData<T, D, S> {
+    pub fn argmin(&self) -> Option<(IndexPair, &T)> {
+        argmin(self)
+    }
+
+    pub fn argmax(&self) -> Option<(IndexPair, &T)> {
+        argmax(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: PartialOrd, D: SquareDimension, S: PackedStorage<T>> UpperTriRawData<T, D, S> {
+    pub fn top_k(&self, k: usize) -> Vec<(IndexPair, &T)> {
+        top_k(self, k)
+    }
+
+    pub fn bottom_k(&self, k: usize) -> Vec<(IndexPair, &T)> {
+        bottom_k(self, k)
+    }
+
+    pub fn sorted_pairs(&self) -> Vec<(IndexPair, &T)> {
+        sorted_pairs(self)
+    }
+}
@@ -0,0 +1,81 @@
+use crate::upper_tri::triangular::TriangularMatrix;
+use crate::upper_tri::*;
+use core::ops::Sub;
+
+fn check_rhs<T, U, M>(matrix: &M, b: &[U]) -> Result<usize, UpperTriError>
+where
+    M: TriangularMatrix<T> + ?Sized,
+{
+    let rank = matrix.rank();
+    if b.len() != rank {
+        return Err(UpperTriError::LengthMismatch {
+            expected: rank,
+            found: b.len(),
+        });
+    }
+    Ok(rank)
+}
+
+/// Solve `U x = b` by back substitution, overwriting `b` with `x`. This works a column at a time
+/// so only contiguous column slices are read. A zero on the diagonal is divided by like any other
+/// element, so for floats the result holds infinities or NaNs
+pub fn back_substitute<T, M>(upper: &M, b: &mut [T]) -> Result<(), UpperTriError>
+where
+    T: Clone + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+    M: TriangularMatrix<T> + ?Sized,
+{
+    let rank = check_rhs(upper, b)?;
+    for col in (0..rank).rev() {
+        let (above, diag) = upper.col_slice(col).split_at(col);
+        let x = b[col].clone() / diag[0].clone();
+        b[..col].iter_mut().zip(above).for_each(|(b_i, u)| {
+            *b_i = b_i.clone() - u.clone() * x.clone();
+        });
+        b[col] = x;
+    }
+    Ok(())
+}
+
+/// Solve `Uᵀ x = b` by forward substitution, overwriting `b` with `x`. Column `i` of `U` is row
+/// `i` of the lower triangular `Uᵀ`, so this is the solve for a `LowerTriRawData`
+pub fn forward_substitute_transposed<T, M>(upper: &M, b: &mut [T]) -> Result<(), UpperTriError>
+where
+    T: Clone + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+    M: TriangularMatrix<T> + ?Sized,
+{
+    let rank = check_rhs(upper, b)?;
+    for col in 0..rank {
+        let (above, diag) = upper.col_slice(col).split_at(col);
+        let (solved, rest) = b.split_at_mut(col);
+        let acc = above
+            .iter()
+            .zip(solved.iter())
+            .fold(rest[0].clone(), |acc, (u, x)| acc - u.clone() * x.clone());
+        rest[0] = acc / diag[0].clone();
+    }
+    Ok(())
+}
+
+/// Solve `L x = b` by forward substitution, overwriting `b` with `x`
+pub fn forward_substitute<T, M>(lower: &M, b: &mut [T]) -> Result<(), UpperTriError>
+where
+    T: Clone + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+    M: LowerTriangular<T> + ?Sized,
+{
+    forward_substitute_transposed(&lower.as_upper(), b)
+}
+
+impl<T, D: SquareDimension, S: PackedStorage<T>> UpperTriRawData<T, D, S>
+where
+    T: Clone + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+{
+    /// Solve `U x = b` in place. See `back_substitute`
+    pub fn solve_inplace(&self, b: &mut [T]) -> Result<(), UpperTriError> {
+        back_substitute(self, b)
+    }
+
+    /// Solve `Uᵀ x = b` in place. See `forward_substitute_transposed`
+    pub fn solve_transposed_inplace(&self, b: &mut [T]) -> Result<(), UpperTriError> {
+        forward_substitute_transposed(self, b)
+    }
+}
@@ -0,0 +1,389 @@
+#[cfg(feature = "alloc")]
+use crate::dimension::HasNext;
+#[cfg(feature = "alloc")]
+use crate::dimension::HasPrev;
+use crate::upper_tri::triangular::element_width;
+use crate::upper_tri::*;
+
+/// The elements strictly above the diagonal of a rank `D` matrix, for antisymmetric matrices or
+/// pairwise distances where the diagonal is known. These are column packed, so (row, col) with
+/// row < col is at offset col * (col - 1) / 2 + row and rank n holds n * (n - 1) / 2 elements.
+/// scipy's condensed distance vectors hold the same pairs row by row; use `from_condensed` and
+/// `to_condensed` to convert
+#[cfg(feature = "alloc")]
+pub struct StrictUpperTri<T, D: SquareDimension + Clone, S = Vec<T>> {
+    buf: S,
+    pub rank: D,
+    marker: PhantomData<T>,
+}
+
+/// The elements strictly above the diagonal of a rank `D` matrix, held in `S`
+#[cfg(not(feature = "alloc"))]
+pub struct StrictUpperTri<T, D: SquareDimension + Clone, S> {
+    buf: S,
+    pub rank: D,
+    marker: PhantomData<T>,
+}
+
+// derived impls would require T: Clone through the marker
+impl<T, D: SquareDimension, S: Clone> Clone for StrictUpperTri<T, D, S> {
+    fn clone(&self) -> Self {
+        StrictUpperTri {
+            buf: self.buf.clone(),
+            rank: self.rank,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, D: SquareDimension, S: Copy> Copy for StrictUpperTri<T, D, S> {}
+
+fn strict_len(rank: usize) -> usize {
+    rank * rank.saturating_sub(1) / 2
+}
+
+/// Offset of (row, col) for row < col
+fn strict_offset(row: usize, col: usize) -> usize {
+    col * (col - 1) / 2 + row
+}
+
+fn check_strict_len(len: usize, rank: usize) -> Result<(), UpperTriError> {
+    let expected = strict_len(rank);
+    if len != expected {
+        Err(UpperTriError::LengthMismatch {
+            expected,
+            found: len,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// The part of column `col` above the diagonal. This is all of the stored column, and column 0
+/// stores nothing
+fn strict_col_range(rank: usize, col: usize) -> Range<usize> {
+    if col == 0 || col >= rank {
+        return 0..0;
+    }
+    strict_offset(0, col)..(strict_offset(0, col) + col)
+}
+
+/// Where the elements (row, col) for col in `row + 1..rank` sit in the buffer. The gap after
+/// the element in column `col` is `col - 1`, so the columns are passed to `Gapped` shifted down
+/// by one
+fn strict_row_layout(rank: usize, row: usize) -> (Range<usize>, Range<usize>) {
+    if row >= rank.saturating_sub(1) {
+        return (0..0, 0..0);
+    }
+    (
+        strict_offset(row, row + 1)..(strict_offset(row, rank - 1) + 1),
+        row..(rank - 1),
+    )
+}
+
+impl<T, D: SquareDimension, S> StrictUpperTri<T, D, S> {
+    const fn from_parts(buf: S, rank: D) -> Self {
+        StrictUpperTri {
+            buf,
+            rank,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn storage(&self) -> &S {
+        &self.buf
+    }
+
+    pub fn into_storage(self) -> S {
+        self.buf
+    }
+}
+
+impl<T, S: PackedStorage<T>> StrictUpperTri<T, DynSquare, S> {
+    /// Take ownership of a column packed buffer of n * (n - 1) / 2 elements
+    pub fn from_packed(rank: usize, buf: S) -> Result<Self, UpperTriError> {
+        check_strict_len(buf.as_slice().len(), rank)?;
+        Ok(Self::from_parts(buf, DynSquare(rank)))
+    }
+}
+
+impl<T, D: StaticDimension, S: PackedStorage<T>> StrictUpperTri<T, D, S> {
+    /// Take ownership of a column packed buffer of n * (n - 1) / 2 elements
+    pub fn from_packed(buf: S) -> Result<Self, UpperTriError> {
+        check_strict_len(buf.as_slice().len(), D::RANK)?;
+        Ok(Self::from_parts(buf, D::DIM))
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorage<T>> StrictUpperTri<T, D, S> {
+    /// The packed elements in column order
+    pub fn as_slice(&self) -> &[T] {
+        self.buf.as_slice()
+    }
+
+    /// `None` on or below the diagonal as well as out of range
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row >= col || col >= self.rank.to_usize() {
+            return None;
+        }
+        self.as_slice().get(strict_offset(row, col))
+    }
+
+    /// The elements above the diagonal in column `col`
+    pub fn get_raw_col(&self, col: usize) -> ColView<'_, T> {
+        ColView(self.col_slice(col).iter())
+    }
+
+    /// The elements right of the diagonal in row `row`
+    pub fn get_raw_row(&self, row: usize) -> RowView<'_, T> {
+        let (range, cols) = strict_row_layout(self.rank.to_usize(), row);
+        RowView(Gapped::new(&self.as_slice()[range], cols, 0))
+    }
+
+    pub fn col_slice(&self, col: usize) -> &[T] {
+        let range = strict_col_range(self.rank.to_usize(), col);
+        &self.as_slice()[range]
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.as_slice().iter()
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorageMut<T>> StrictUpperTri<T, D, S> {
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.buf.as_mut_slice()
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        if row >= col || col >= self.rank.to_usize() {
+            return None;
+        }
+        self.as_mut_slice().get_mut(strict_offset(row, col))
+    }
+
+    pub fn get_raw_col_mut(&mut self, col: usize) -> ColViewMut<'_, T> {
+        ColViewMut(self.col_slice_mut(col).iter_mut())
+    }
+
+    pub fn get_raw_row_mut(&mut self, row: usize) -> RowViewMut<'_, T> {
+        let (range, cols) = strict_row_layout(self.rank.to_usize(), row);
+        RowViewMut(Gapped::new(&mut self.as_mut_slice()[range], cols, 0))
+    }
+
+    pub fn col_slice_mut(&mut self, col: usize) -> &mut [T] {
+        let range = strict_col_range(self.rank.to_usize(), col);
+        &mut self.as_mut_slice()[range]
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
+    }
+}
+
+/// Panics if the position is out of range or not strictly above the diagonal
+impl<T, D: SquareDimension, S: PackedStorage<T>> Index<(usize, usize)> for StrictUpperTri<T, D, S> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        let rank = self.rank.to_usize();
+        match self.get(row, col) {
+            Some(t) => t,
+            None => panic!("index ({}, {}) out of range for rank {}", row, col, rank),
+        }
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorageMut<T>> IndexMut<(usize, usize)>
+    for StrictUpperTri<T, D, S>
+{
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let rank = self.rank.to_usize();
+        match self.get_mut(row, col) {
+            Some(t) => t,
+            None => panic!("index ({}, {}) out of range for rank {}", row, col, rank),
+        }
+    }
+}
+
+impl<'a, T, D, S, S2> AddAssign<&'a StrictUpperTri<T, D, S2>> for StrictUpperTri<T, D, S>
+where
+    T: for<'b> AddAssign<&'b T>,
+    D: SquareDimension,
+    S: PackedStorageMut<T>,
+    S2: PackedStorage<T>,
+{
+    fn add_assign(&mut self, rhs: &'a StrictUpperTri<T, D, S2>) {
+        self.iter_mut()
+            .zip(rhs.iter())
+            .for_each(|(left, right)| *left += right);
+    }
+}
+
+impl<'a, T, D, S, S2> SubAssign<&'a StrictUpperTri<T, D, S2>> for StrictUpperTri<T, D, S>
+where
+    T: for<'b> SubAssign<&'b T>,
+    D: SquareDimension,
+    S: PackedStorageMut<T>,
+    S2: PackedStorage<T>,
+{
+    fn sub_assign(&mut self, rhs: &'a StrictUpperTri<T, D, S2>) {
+        self.iter_mut()
+            .zip(rhs.iter())
+            .for_each(|(left, right)| *left -= right);
+    }
+}
+
+/// Laid out like the `UpperTriRawData` display with the diagonal left blank
+impl<T: Display, D: SquareDimension, S: PackedStorage<T>> Display for StrictUpperTri<T, D, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::result::Result<(), core::fmt::Error> {
+        let width = element_width(self.iter())?;
+        writeln!(f)?;
+        for row in 0..self.rank.to_usize().saturating_sub(1) {
+            write!(f, "\t{:indent$}", "", indent = (width + 1) * (row + 1))?;
+            for t in self.get_raw_row(row) {
+                write!(f, "{:>width$} ", t, width = width)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn strict_from_fn<T, F: FnMut(usize, usize) -> T>(rank: usize, mut f: F) -> Vec<T> {
+    let mut buf = Vec::with_capacity(strict_len(rank));
+    for col in 1..rank {
+        buf.extend((0..col).map(|row| f(row, col)));
+    }
+    buf
+}
+
+#[cfg(feature = "alloc")]
+impl<T> StrictUpperTri<T, DynSquare> {
+    /// Build a matrix from the value at each position strictly above the diagonal
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(rank: usize, f: F) -> Self {
+        Self::from_parts(strict_from_fn(rank, f), DynSquare(rank))
+    }
+
+    /// Remove row and column `index`, returning the removed column above the diagonal followed
+    /// by the removed row to its right
+    pub fn drop_at(&mut self, index: usize) -> Vec<T> {
+        let rank = self.rank.to_usize();
+        assert!(
+            index < rank,
+            "drop index {} out of range for rank {}",
+            index,
+            rank
+        );
+        let mut return_vec = Vec::with_capacity(rank - 1);
+        return_vec.extend(self.buf.drain(strict_col_range(rank, index)));
+        // by the time we reach `col` we have removed the `index` elements of the dropped column
+        // and one from each of the `col - index - 1` columns before it
+        for col in (index + 1)..rank {
+            let t = self.buf.remove(strict_offset(index, col) - (col - 1));
+            return_vec.push(t);
+        }
+        self.rank.shrink();
+        return_vec
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Zero> StrictUpperTri<T, DynSquare> {
+    pub fn new(rank: usize) -> Self {
+        let buf = repeat_with(T::zero).take(strict_len(rank)).collect();
+        Self::from_parts(buf, DynSquare(rank))
+    }
+
+    /// Add a final column holding rows `0..rank` of the old rank, filling with zero if the
+    /// iterator runs short
+    pub fn push_final_col_iter_owned<Itr: Iterator<Item = T>>(&mut self, iter: Itr) {
+        let new = self.rank.to_usize();
+        self.buf.reserve(new);
+        self.buf.extend(iter.chain(repeat_with(T::zero)).take(new));
+        self.rank.grow();
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone + Zero> StrictUpperTri<T, DynSquare> {
+    pub fn push_final_col(&mut self, vec: &[T]) {
+        self.push_final_col_iter_owned(vec.iter().cloned())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone> StrictUpperTri<T, DynSquare> {
+    /// Read scipy's condensed form, which lists (i, j) for i < j row by row
+    pub fn from_condensed(rank: usize, condensed: &[T]) -> Result<Self, UpperTriError> {
+        check_strict_len(condensed.len(), rank)?;
+        Ok(Self::from_fn(rank, |row, col| {
+            condensed[rank * row - row * (row + 1) / 2 + col - row - 1].clone()
+        }))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone, D: SquareDimension, S: PackedStorage<T>> StrictUpperTri<T, D, S> {
+    /// The elements in scipy's condensed order, row by row
+    pub fn to_condensed(&self) -> Vec<T> {
+        (0..self.rank.to_usize())
+            .flat_map(|row| self.get_raw_row(row).cloned())
+            .collect()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, D: StaticDimension> StrictUpperTri<T, D> {
+    /// Build a matrix from the value at each position strictly above the diagonal
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(f: F) -> Self {
+        Self::from_parts(strict_from_fn(D::RANK, f), D::DIM)
+    }
+
+    /// Run a rank changing operation on the dynamic form, then label the result with the static
+    /// rank it is known to end up with
+    fn resize_via_dyn<E: SquareDimension, R, F>(self, rank: E, f: F) -> (StrictUpperTri<T, E>, R)
+    where
+        F: FnOnce(&mut StrictUpperTri<T, DynSquare>) -> R,
+    {
+        let mut dyn_data = StrictUpperTri::from_parts(self.buf, DynSquare(D::RANK));
+        let out = f(&mut dyn_data);
+        debug_assert_eq!(dyn_data.rank.to_usize(), rank.to_usize());
+        (StrictUpperTri::from_parts(dyn_data.buf, rank), out)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Zero, D: StaticDimension> StrictUpperTri<T, D> {
+    pub fn new() -> Self {
+        let buf = repeat_with(T::zero).take(strict_len(D::RANK)).collect();
+        Self::from_parts(buf, D::DIM)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Zero, D: StaticDimension> Default for StrictUpperTri<T, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bordering operations. These consume the matrix since the static rank changes
+#[cfg(feature = "alloc")]
+impl<T: Clone + Zero, D: StaticDimension + HasNext> StrictUpperTri<T, D> {
+    pub fn push_final_col(self, vec: &[T]) -> StrictUpperTri<T, D::Next> {
+        let rank = self.rank.next();
+        self.resize_via_dyn(rank, |grown| grown.push_final_col(vec))
+            .0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, D: StaticDimension + HasPrev> StrictUpperTri<T, D> {
+    /// See the dynamic `drop_at`
+    pub fn drop_at(self, index: usize) -> (StrictUpperTri<T, D::Prev>, Vec<T>) {
+        let rank = self.rank.prev();
+        self.resize_via_dyn(rank, |shrunk| shrunk.drop_at(index))
+    }
+}
@@ -0,0 +1,37 @@
+use crate::upper_tri::*;
+
+/// Copy out the rows and columns at `indices`. The result follows the order of `indices`, so for
+/// unsorted indices entries are mirrored into the upper triangle as needed
+pub fn principal_submatrix<T, M>(
+    matrix: &M,
+    indices: &[usize],
+) -> Result<UpperTriRawData<T, DynSquare>, UpperTriError>
+where
+    T: Clone,
+    M: TriangularMatrix<T> + ?Sized,
+{
+    let rank = matrix.rank();
+    if let Some(&index) = indices.iter().find(|index| **index >= rank) {
+        return Err(UpperTriError::IndexOutOfRange { index, rank });
+    }
+    let packed = matrix.as_slice();
+    let new = indices.len();
+    let mut buf = Vec::with_capacity(new * (new + 1) / 2);
+    for (col, &j) in indices.iter().enumerate() {
+        buf.extend(
+            indices[..=col]
+                .iter()
+                .map(|&i| packed[symmetric_offset(i, j)].clone()),
+        );
+    }
+    Ok(UpperTriRawData::from_parts(buf, DynSquare(new)))
+}
+
+impl<T: Clone, D: SquareDimension, S: PackedStorage<T>> UpperTriRawData<T, D, S> {
+    pub fn principal_submatrix(
+        &self,
+        indices: &[usize],
+    ) -> Result<UpperTriRawData<T, DynSquare>, UpperTriError> {
+        principal_submatrix(self, indices)
+    }
+}
@@ -1,8 +1,14 @@
 use crate::dimension::*;
-use crate::upper_tri::upper_tri_dyn::*;
-use crate::upper_tri::upper_tri_stc::*;
+use crate::error::UpperTriError;
+use crate::upper_tri::interval_dp::*;
+use crate::upper_tri::view::*;
 use crate::upper_tri::*;
+use std::boxed::Box;
+use std::convert::TryFrom;
+use std::format;
 use std::iter::repeat;
+use std::string::String;
+use std::string::ToString;
 
 #[test]
 /// The row and column access is strictly above and to the right of the diagonal so the
@@ -17,7 +23,7 @@ fn test_sizes() {
         *x = val;
         val += 1.0;
     });
-    let c = b.get_raw_row(row_index).map(|x| *x).collect::<Vec<_>>();
+    let c = b.get_raw_row(row_index).copied().collect::<Vec<_>>();
     assert_eq!(
         c,
         (0..(hundurd - row_index - 1))
@@ -97,12 +103,11 @@ fn test_creation() {
     // dropping the "corner" at the fifth element
     // (arrays start at 0)
     upper_tri.drop_at(4);
-    let col = upper_tri.get_raw_col(9).map(|x| *x).collect::<Vec<_>>();
-    assert_eq!(vec![0, 1, 2, 3, 4, 6, 7, 8, 9], col);
+    let col = upper_tri.get_raw_col(9).copied().collect::<Vec<_>>();
+    assert_eq!(vec![0, 1, 2, 3, 5, 6, 7, 8, 9], col);
 }
 #[test]
 fn test_addition() {
-    let ten: usize = 10;
     let mut upper_tri_1 = UpperTriRawData::<isize, DynSquare>::new(1);
     let mut upper_tri_2 = UpperTriRawData::<isize, DynSquare>::new(1);
     upper_tri_1.push_final_col_iter(repeat(&(-1)));
@@ -111,10 +116,18 @@ fn test_addition() {
     upper_tri_2.push_final_col_iter(repeat(&1));
 
     let upper_tri_3 = upper_tri_1 + upper_tri_2;
-    let second_col = upper_tri_3.get_raw_col(1).map(|x| *x).collect::<Vec<_>>();
-    let third_col = upper_tri_3.get_raw_col(2).map(|x| *x).collect::<Vec<_>>();
+    let second_col = upper_tri_3.get_raw_col(1).copied().collect::<Vec<_>>();
+    let third_col = upper_tri_3.get_raw_col(2).copied().collect::<Vec<_>>();
     assert_eq!(second_col, vec![0]);
     assert_eq!(third_col, vec![2, 2]);
+
+    // ranks that differ combine over the shared leading block
+    let mut large = sample(3);
+    large += &sample(2);
+    assert_eq!(large.as_slice(), &[0, 2, 22, 2, 12, 22]);
+    large -= &sample(2);
+    assert_eq!(large.as_slice(), sample(3).as_slice());
+    assert_eq!((&sample(2) + &sample(3)).rank.to_usize(), 3);
 }
 
 #[test]
@@ -133,3 +146,896 @@ fn test_static() {
     let corner_sum: i64 = stc_upper_tri_3.get_corner(4).sum();
     assert_eq!(corner_sum, (ONE + TWO) * (DIM as i64));
 }
+
+#[test]
+fn test_zip_combinators() {
+    let mut left = UpperTriRawData::<i64, DynSquare>::new(0);
+    let mut right = UpperTriRawData::<i64, DynSquare>::new(0);
+    for i in 0..4 {
+        left.push_final_col_iter_owned(0..(i + 1));
+        right.push_final_col_iter_owned(repeat(i + 1));
+    }
+
+    let product = left.hadamard(&right).unwrap();
+    assert_eq!(
+        product.get_raw_col(3).copied().collect::<Vec<_>>(),
+        vec![0, 4, 8]
+    );
+    let quotient = product.hadamard_div(&right).unwrap();
+    assert_eq!(
+        quotient.iter().collect::<Vec<_>>(),
+        left.iter().collect::<Vec<_>>()
+    );
+
+    let mask = left.map(|x| *x % 2 == 0);
+    let blended = left
+        .zip3_map(&right, &mask, |l, r, keep| if *keep { *l } else { *r })
+        .unwrap();
+    assert_eq!(blended.get(1, 3), Some(&4));
+    assert_eq!(blended.get(2, 3), Some(&2));
+
+    left.zip_apply(&right, |l, r| *l -= r).unwrap();
+    assert_eq!(left.get(0, 0), Some(&-1));
+
+    let small = UpperTriRawData::<i64, DynSquare>::new(3);
+    assert_eq!(
+        left.hadamard(&small).err(),
+        Some(UpperTriError::RankMismatch {
+            expected: 4,
+            found: 3
+        })
+    );
+}
+
+#[test]
+fn test_conversion_and_resize() {
+    let stc = UpperTriRawData::<i64, StcSquare<3>>::new_with(7);
+    let mut dynamic: UpperTriRawData<i64, DynSquare> = stc.into();
+    assert_eq!(dynamic.rank.to_usize(), 3);
+
+    dynamic.resize(5, 1);
+    assert_eq!(
+        dynamic.get_raw_col(4).copied().collect::<Vec<_>>(),
+        vec![1; 4]
+    );
+    assert_eq!(dynamic.get(2, 2), Some(&7));
+    assert!(UpperTriRawData::<i64, StcSquare<3>>::try_from(dynamic.clone()).is_err());
+
+    dynamic.truncate(3);
+    let back = UpperTriRawData::<i64, StcSquare<3>>::try_from(dynamic).unwrap();
+    assert!(back.iter().all(|x| *x == 7));
+
+    let mut grown = UpperTriRawData::<i64, DynSquare>::with_capacity(10);
+    assert_eq!(grown.rank.to_usize(), 0);
+    grown.push_final_col(&[3]);
+    assert_eq!(grown.get(0, 0), Some(&3));
+}
+
+/// Element (row, col) holds 10 * row + col, so positions can be read off the values
+fn sample(rank: usize) -> UpperTriRawData<usize, DynSquare> {
+    UpperTriRawData::<usize, DynSquare>::from_fn(rank, |row, col| 10 * row + col)
+}
+
+#[test]
+fn test_insert_at_inverts_drop_at() {
+    let mut upper_tri = sample(8);
+    let original = upper_tri.clone();
+
+    for index in 0..8 {
+        let dropped = upper_tri.drop_at(index);
+        assert_eq!(dropped.len(), 8);
+        upper_tri.insert_at(index, &dropped);
+        assert_eq!(
+            upper_tri.iter().collect::<Vec<_>>(),
+            original.iter().collect::<Vec<_>>()
+        );
+    }
+
+    upper_tri.insert_at(8, &[1, 2]);
+    assert_eq!(upper_tri.get(1, 8), Some(&2));
+    assert_eq!(upper_tri.get(8, 8), Some(&0));
+}
+
+#[test]
+#[should_panic(expected = "drop index 3 out of range for rank 3")]
+fn test_drop_at_out_of_range_panics() {
+    sample(3).drop_at(3);
+}
+
+#[test]
+fn test_drop_many_matches_drop_at() {
+    let mut upper_tri = sample(9);
+
+    let mut one_at_a_time = upper_tri.clone();
+    for index in [7, 4, 1].iter() {
+        one_at_a_time.drop_at(*index);
+    }
+    let expected_7 = upper_tri.clone().drop_at(7);
+
+    let removed = upper_tri.drop_many(&[4, 1, 7]);
+    assert_eq!(upper_tri.rank.to_usize(), 6);
+    assert_eq!(
+        upper_tri.iter().collect::<Vec<_>>(),
+        one_at_a_time.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(removed.len(), 3);
+    assert_eq!(removed[2], expected_7);
+
+    let removed = upper_tri.retain_indices(|index| index % 2 == 0);
+    assert_eq!(removed.len(), 3);
+    assert_eq!(
+        upper_tri.get_diag().copied().collect::<Vec<_>>(),
+        vec![0, 33, 66]
+    );
+}
+
+#[test]
+fn test_permute_symmetric() {
+    let rank = 6;
+    let upper_tri = sample(rank);
+    let perm = vec![3, 0, 4, 5, 1, 2];
+    let permuted = upper_tri.permute_symmetric(&perm).unwrap();
+    for col in 0..rank {
+        for row in 0..=col {
+            let (new_row, new_col) = (perm[row], perm[col]);
+            let expected = permuted
+                .get(new_row.min(new_col), new_row.max(new_col))
+                .unwrap();
+            assert_eq!(upper_tri.get(row, col).unwrap(), expected);
+        }
+    }
+
+    let mut inplace = upper_tri.clone();
+    inplace.permute_symmetric_inplace(&perm).unwrap();
+    assert_eq!(
+        inplace.iter().collect::<Vec<_>>(),
+        permuted.iter().collect::<Vec<_>>()
+    );
+
+    inplace.swap_indices(1, 4);
+    inplace.swap_indices(4, 1);
+    assert_eq!(
+        inplace.iter().collect::<Vec<_>>(),
+        permuted.iter().collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        upper_tri.permute_symmetric(&[0, 0, 1, 2, 3, 4]).err(),
+        Some(UpperTriError::InvalidPermutation { rank })
+    );
+}
+
+#[test]
+fn test_principal_submatrix() {
+    let upper_tri = sample(7);
+    let sub = upper_tri.principal_submatrix(&[5, 1, 3]).unwrap();
+    assert_eq!(sub.rank.to_usize(), 3);
+    assert_eq!(
+        sub.get_diag().copied().collect::<Vec<_>>(),
+        vec![55, 11, 33]
+    );
+    assert_eq!(sub.get(0, 1), Some(&15));
+    assert_eq!(sub.get(0, 2), Some(&35));
+    assert_eq!(sub.get(1, 2), Some(&13));
+
+    let leading = upper_tri.principal_submatrix(&[0, 1, 2]).unwrap();
+    assert_eq!(
+        leading.iter().collect::<Vec<_>>(),
+        upper_tri.iter().take(6).collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        upper_tri.principal_submatrix(&[2, 7]).err(),
+        Some(UpperTriError::IndexOutOfRange { index: 7, rank: 7 })
+    );
+}
+
+#[test]
+fn test_views() {
+    let mut upper_tri = sample(6);
+
+    let leading = upper_tri.leading(3).unwrap();
+    assert_eq!(leading.rank.to_usize(), 3);
+    assert_eq!(leading.get(1, 2), Some(&12));
+    assert_eq!(leading.get(1, 3), None);
+    assert_eq!(
+        leading.get_raw_row(0).copied().collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    assert_eq!(
+        leading.get_corner(1).copied().collect::<Vec<_>>(),
+        vec![1, 11, 12]
+    );
+    assert!(upper_tri.leading(7).is_err());
+
+    upper_tri
+        .leading_mut(2)
+        .unwrap()
+        .get_diag_mut()
+        .for_each(|x| *x = 0);
+    assert_eq!(
+        upper_tri.get_diag().copied().collect::<Vec<_>>(),
+        vec![0, 0, 22, 33, 44, 55]
+    );
+
+    let packed = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+    let view = UpperTriView::<f64, StcSquare<3>>::from_slice(&packed).unwrap();
+    assert_eq!(
+        view.get_raw_col(2).copied().collect::<Vec<_>>(),
+        vec![4.0, 5.0]
+    );
+    assert_eq!(
+        view.get_diag().copied().collect::<Vec<_>>(),
+        vec![1.0, 3.0, 6.0]
+    );
+    assert_eq!(
+        UpperTriView::<f64, DynSquare>::from_slice(&packed[..5], 3).err(),
+        Some(UpperTriError::LengthMismatch {
+            expected: 6,
+            found: 5
+        })
+    );
+
+    let mut external = vec![0; 10];
+    let mut view_mut = UpperTriViewMut::<i32, DynSquare>::from_slice(&mut external, 4).unwrap();
+    view_mut.get_raw_row_mut(1).for_each(|x| *x = 7);
+    *view_mut.get_mut(3, 3).unwrap() = 9;
+    assert_eq!(external, vec![0, 0, 0, 0, 7, 0, 0, 7, 0, 9]);
+}
+
+#[test]
+fn test_direct_sum_and_join() {
+    let mut left = UpperTriRawData::<i32, DynSquare>::new(0);
+    left.push_final_col(&[1]);
+    left.push_final_col(&[2, 3]);
+    let mut right = UpperTriRawData::<i32, DynSquare>::new(0);
+    right.push_final_col(&[4]);
+    right.push_final_col(&[5, 6]);
+    right.push_final_col(&[7, 8, 9]);
+
+    let sum = left.direct_sum(&right);
+    assert_eq!(sum.rank.to_usize(), 5);
+    assert_eq!(
+        sum.get_diag().copied().collect::<Vec<_>>(),
+        vec![1, 3, 4, 6, 9]
+    );
+    assert_eq!(
+        sum.get_raw_col(4).copied().collect::<Vec<_>>(),
+        vec![0, 0, 7, 8]
+    );
+    let filled = left.direct_sum_with(&right, -1);
+    assert_eq!(filled.get(1, 2), Some(&-1));
+    assert_eq!(filled.get(2, 3), Some(&5));
+
+    let cross = (10..16).collect::<Vec<_>>();
+    let joined = UpperTriRawData::join(&left, &right, &cross).unwrap();
+    assert_eq!(
+        joined.get_raw_row(1).copied().collect::<Vec<_>>(),
+        vec![11, 13, 15]
+    );
+    assert_eq!(
+        joined.get_raw_col(4).copied().collect::<Vec<_>>(),
+        vec![14, 15, 7, 8]
+    );
+    assert!(UpperTriRawData::join(&left, &right, &cross[1..]).is_err());
+}
+
+#[test]
+fn test_superdiagonals() {
+    let mut upper_tri = sample(5);
+    let first = upper_tri.get_superdiag(1);
+    assert_eq!(first.len(), 4);
+    assert_eq!(first.copied().collect::<Vec<_>>(), vec![1, 12, 23, 34]);
+    assert_eq!(
+        upper_tri.get_superdiag(4).copied().collect::<Vec<_>>(),
+        vec![4]
+    );
+    assert_eq!(upper_tri.get_superdiag(5).len(), 0);
+
+    upper_tri.get_superdiag_mut(2).for_each(|x| *x = 0);
+    assert_eq!(upper_tri.get(1, 3), Some(&0));
+
+    let order = upper_tri.iter_by_superdiag();
+    assert_eq!(order.len(), 15);
+    let spans = order
+        .map(|(IndexPair { row, col }, _)| col - row)
+        .collect::<Vec<_>>();
+    assert!(spans.windows(2).all(|w| w[0] <= w[1]));
+    let (last, value) = upper_tri.iter_by_superdiag().last().unwrap();
+    assert_eq!(last, IndexPair { row: 0, col: 4 });
+    assert_eq!(*value, 4);
+}
+
+#[test]
+fn test_interval_dp_matrix_chain() {
+    // matrix i has shape dims[i] x dims[i + 1]
+    let dims = [10u64, 30, 5, 60, 10];
+    let n = dims.len() - 1;
+    let dp = IntervalDp::solve(n, |IndexPair { row, col }, sub| {
+        (row..col)
+            .map(|k| {
+                let cost = sub.get(row, k).unwrap()
+                    + sub.get(k + 1, col).unwrap()
+                    + dims[row] * dims[k + 1] * dims[col + 1];
+                (cost, Some(k))
+            })
+            .min()
+            .unwrap_or((0u64, None))
+    });
+
+    assert_eq!(dp.value(0, 2), Some(&4500));
+    assert_eq!(dp.split(0, 2), Some(1));
+    assert_eq!(dp.value(0, 3), Some(&5000));
+    assert_eq!(dp.split(2, 2), None);
+    let leaf = |i| Box::new(SplitTree::Leaf(IndexPair { row: i, col: i }));
+    assert_eq!(
+        dp.split_tree(0, 2),
+        Some(SplitTree::Split {
+            interval: IndexPair { row: 0, col: 2 },
+            left: Box::new(SplitTree::Split {
+                interval: IndexPair { row: 0, col: 1 },
+                left: leaf(0),
+                right: leaf(1),
+            }),
+            right: leaf(2),
+        })
+    );
+}
+
+#[test]
+fn test_views_are_exact_and_double_ended() {
+    fn assert_send_sync<S: Send + Sync>(_: &S) {}
+
+    let mut upper_tri = sample(6);
+
+    let mut col = upper_tri.get_raw_col(4);
+    assert_send_sync(&col);
+    assert_eq!(col.len(), 4);
+    col.next();
+    assert_eq!(col.len(), 3);
+    assert_eq!(col.rev().copied().collect::<Vec<_>>(), vec![34, 24, 14]);
+
+    let row = upper_tri.get_raw_row(2);
+    assert_eq!(row.len(), 3);
+    assert_eq!(row.rev().copied().collect::<Vec<_>>(), vec![25, 24, 23]);
+    assert_eq!(upper_tri.get_raw_row(6).len(), 0);
+    assert_eq!(upper_tri.get_raw_row(60).next(), None);
+    assert_eq!(upper_tri.get_raw_row(usize::MAX).next(), None);
+
+    let mut diag = upper_tri.get_diag();
+    assert_eq!(diag.next_back(), Some(&55));
+    assert_eq!(diag.next(), Some(&0));
+    assert_eq!(diag.len(), 4);
+    assert_eq!(diag.copied().collect::<Vec<_>>(), vec![11, 22, 33, 44]);
+
+    let mut corner = upper_tri.get_corner(2);
+    assert_eq!(corner.len(), 6);
+    assert_eq!(corner.next_back(), Some(&25));
+    assert_eq!(corner.copied().collect::<Vec<_>>(), vec![2, 12, 22, 23, 24]);
+
+    let mut superdiag = upper_tri.get_superdiag(2);
+    assert_eq!(superdiag.next_back(), Some(&35));
+    assert_eq!(superdiag.next(), Some(&2));
+    assert_eq!(superdiag.next_back(), Some(&24));
+    assert_eq!(superdiag.next(), Some(&13));
+    assert_eq!(superdiag.next(), None);
+    assert_eq!(superdiag.next_back(), None);
+
+    let mut corner_mut = upper_tri.get_corner_mut(3);
+    assert_send_sync(&corner_mut);
+    let first = corner_mut.next().unwrap();
+    let last = corner_mut.next_back().unwrap();
+    *first += 100;
+    *last += 100;
+    assert_eq!(corner_mut.len(), 4);
+    corner_mut.rev().for_each(|x| *x = 0);
+    assert_eq!(
+        upper_tri.get_corner(3).copied().collect::<Vec<_>>(),
+        vec![103, 0, 0, 0, 0, 135]
+    );
+
+    upper_tri.get_diag_mut().rev().for_each(|x| *x += 1);
+    assert_eq!(
+        upper_tri.get_diag().copied().collect::<Vec<_>>(),
+        vec![1, 12, 23, 1, 45, 56]
+    );
+}
+
+#[test]
+fn test_column_slices() {
+    let mut upper_tri = sample(5);
+    assert_eq!(upper_tri.col_slice(2), &[2, 12, 22]);
+    assert!(upper_tri.col_slice(5).is_empty());
+    upper_tri
+        .col_slice_mut(1)
+        .iter_mut()
+        .for_each(|x| *x += 100);
+    assert_eq!(upper_tri.get(1, 1), Some(&111));
+
+    let columns = upper_tri.columns();
+    assert_eq!(columns.len(), 5);
+    assert_eq!(
+        columns.map(|c| c.len()).collect::<Vec<_>>(),
+        vec![1, 2, 3, 4, 5]
+    );
+    assert_eq!(
+        upper_tri.columns().next_back(),
+        Some(&[4, 14, 24, 34, 44][..])
+    );
+
+    let mut columns_mut = upper_tri.columns_mut();
+    let first = columns_mut.next().unwrap();
+    let last = columns_mut.next_back().unwrap();
+    first[0] = 7;
+    last.iter_mut().for_each(|x| *x = 0);
+    columns_mut.for_each(|column| column.reverse());
+    assert_eq!(upper_tri.get(0, 0), Some(&7));
+    assert_eq!(upper_tri.col_slice(4), &[0, 0, 0, 0, 0]);
+    assert_eq!(upper_tri.col_slice(3), &[33, 23, 13, 3]);
+}
+
+#[test]
+fn test_full_rows() {
+    let mut upper_tri = sample(4).map(|&x| x as i64);
+    let sym = upper_tri.sym_row(2);
+    assert_eq!(sym.len(), 4);
+    assert_eq!(sym.copied().collect::<Vec<_>>(), vec![2, 12, 22, 23]);
+
+    let skew = upper_tri.skew_row(2);
+    assert_eq!(skew.len(), 4);
+    assert_eq!(skew.collect::<Vec<_>>(), vec![-2, -12, 22, 23]);
+    assert_eq!(
+        upper_tri.skew_row(1).rev().collect::<Vec<_>>(),
+        vec![13, 12, 11, -1]
+    );
+    assert_eq!(upper_tri.skew_row(4).len(), 0);
+
+    upper_tri.sym_row_mut(1).for_each(|x| *x *= 2);
+    assert_eq!(
+        upper_tri.sym_row(1).copied().collect::<Vec<_>>(),
+        vec![2, 22, 24, 26]
+    );
+    assert_eq!(upper_tri.get(0, 2), Some(&2));
+}
+
+#[test]
+fn test_indexed_iteration() {
+    let mut upper_tri = sample(4);
+    let indexed = upper_tri.indexed_iter();
+    assert_eq!(indexed.len(), 10);
+    for (IndexPair { row, col }, value) in indexed {
+        assert_eq!(*value, 10 * row + col);
+    }
+
+    let strict = upper_tri.strict_upper_iter();
+    assert_eq!(strict.len(), 6);
+    assert!(strict
+        .map(|(pair, _)| pair)
+        .all(|IndexPair { row, col }| row < col));
+
+    upper_tri
+        .strict_upper_iter_mut()
+        .for_each(|(_, value)| *value = 0);
+    assert_eq!(upper_tri.iter().filter(|x| **x != 0).count(), 3);
+
+    let last = upper_tri.clone().into_indexed_iter().last();
+    assert_eq!(last, Some((IndexPair { row: 3, col: 3 }, 33)));
+
+    let (found, _) = upper_tri.find_with_indices(|found| *found, |t, _| *t == 22);
+    assert_eq!(found, IndexPair { row: 2, col: 2 });
+    let (missing, _) = upper_tri.find_with_indices(|found| *found, |t, _| *t == 5);
+    assert_eq!(missing, IndexPair { row: 0, col: 4 });
+}
+
+#[test]
+fn test_indexed_reductions() {
+    let values = [0.5, 0.1, 0.9, f64::NAN, 0.7, 0.2, 0.9, 0.0, 0.3, 1.0];
+    let mut upper_tri = UpperTriRawData::<f64, DynSquare>::new(4);
+    upper_tri
+        .iter_mut()
+        .zip(values.iter())
+        .for_each(|(t, v)| *t = *v);
+    let at = |row, col| IndexPair { row, col };
+
+    assert_eq!(upper_tri.argmax(), Some((at(3, 3), &1.0)));
+    assert_eq!(upper_tri.argmin(), Some((at(1, 3), &0.0)));
+    assert_eq!(
+        upper_tri.strict_upper_iter().argmax(),
+        Some((at(0, 3), &0.9))
+    );
+    assert_eq!(
+        upper_tri.as_view().strict_upper_iter().argmin(),
+        Some((at(1, 3), &0.0))
+    );
+
+    assert_eq!(
+        upper_tri.top_k(3),
+        vec![(at(3, 3), &1.0), (at(1, 1), &0.9), (at(0, 3), &0.9)]
+    );
+    assert_eq!(
+        upper_tri.strict_upper_iter().bottom_k(2),
+        vec![(at(1, 3), &0.0), (at(0, 1), &0.1)]
+    );
+    let sorted = upper_tri.sorted_pairs();
+    assert_eq!(sorted.len(), 9);
+    assert!(sorted.windows(2).all(|w| w[0].1 <= w[1].1));
+}
+
+#[test]
+fn test_index_and_offsets() {
+    let mut upper_tri = sample(5);
+    assert_eq!(upper_tri[(1, 3)], 13);
+    upper_tri[(2, 4)] = 0;
+    assert_eq!(upper_tri[IndexPair::sorted(4, 2)], 0);
+    upper_tri[IndexPair::new(0, 0).unwrap()] += 5;
+    assert_eq!(upper_tri.get(0, 0), Some(&5));
+    assert_eq!(IndexPair::new(3, 1), None);
+
+    for offset in 0..upper_tri.iter().len() {
+        let pair = pair_of(offset);
+        assert!(pair.row <= pair.col);
+        assert_eq!(offset_of(pair), offset);
+    }
+    assert_eq!(pair_of(10), IndexPair { row: 0, col: 4 });
+    assert_eq!(pair_of(500_500), IndexPair { row: 0, col: 1000 });
+    assert_eq!(pair_of(500_499), IndexPair { row: 999, col: 999 });
+    let last = pair_of(usize::MAX);
+    assert!(last.row <= last.col);
+    let col = last.col as u128;
+    assert_eq!(col * (col + 1) / 2 + last.row as u128, usize::MAX as u128);
+}
+
+#[test]
+#[should_panic]
+fn test_index_below_diagonal_panics() {
+    let upper_tri = UpperTriRawData::<usize, DynSquare>::new(3);
+    let _ = upper_tri[(2, 1)];
+}
+
+#[test]
+fn test_constructors() {
+    let from_fn = UpperTriRawData::<usize, DynSquare>::from_fn(4, |row, col| 10 * row + col);
+    assert_eq!(from_fn.get(2, 3), Some(&23));
+    assert_eq!(from_fn.iter().len(), 10);
+
+    let stc = UpperTriRawData::<usize, StcSquare<4>>::from_fn(|row, col| 10 * row + col);
+    assert_eq!(
+        stc.iter().collect::<Vec<_>>(),
+        from_fn.iter().collect::<Vec<_>>()
+    );
+
+    let packed =
+        UpperTriRawData::<usize, DynSquare>::from_packed(4, from_fn.clone().into_iter().collect())
+            .unwrap();
+    assert_eq!(packed.get(1, 3), Some(&13));
+    assert_eq!(
+        UpperTriRawData::<usize, StcSquare<3>>::from_packed(vec![0; 5]).err(),
+        Some(UpperTriError::LengthMismatch {
+            expected: 6,
+            found: 5
+        })
+    );
+
+    let mut collected = (0..4)
+        .map(|col| (0..=col).map(move |row| 10 * row + col))
+        .collect::<UpperTriRawData<usize, DynSquare>>();
+    assert_eq!(
+        collected.iter().collect::<Vec<_>>(),
+        from_fn.iter().collect::<Vec<_>>()
+    );
+    collected.extend(vec![vec![1, 2], vec![3; 10]]);
+    assert_eq!(collected.rank.to_usize(), 6);
+    assert_eq!(collected.col_slice(4), &[1, 2, 0, 0, 0]);
+    assert_eq!(collected.col_slice(5), &[3; 6]);
+}
+
+#[test]
+fn test_non_copy_elements() {
+    let mut labels =
+        UpperTriRawData::<String, DynSquare>::from_fn(3, |row, col| format!("{}-{}", row, col));
+    assert_eq!(labels.get(1, 2).map(|s| s.as_str()), Some("1-2"));
+    assert_eq!(labels[(0, 2)], "0-2");
+    labels.get_raw_row_mut(0).for_each(|s| s.push('!'));
+    assert_eq!(
+        labels.sym_row(1).cloned().collect::<Vec<_>>(),
+        vec!["0-1!", "1-1", "1-2"]
+    );
+
+    let dropped = labels.drop_at(1);
+    assert_eq!(dropped, vec!["0-1!", "1-1", "1-2"]);
+    let removed = labels.drop_many(&[0]);
+    assert_eq!(removed, vec![vec!["0-0", "0-2!"]]);
+    assert_eq!(labels.to_string(), "\n\t2-2 \n");
+
+    // no Clone at all
+    struct Alignment(Vec<f32>);
+    let alignments = UpperTriRawData::<Alignment, DynSquare>::from_fn(2, |row, col| {
+        Alignment(vec![0.0; row + col])
+    });
+    assert_eq!(alignments.get(1, 1).map(|a| a.0.len()), Some(2));
+    let lengths = alignments.map(|a| a.0.len());
+    assert_eq!(lengths.iter().sum::<usize>(), 3);
+}
+
+#[test]
+/// Views over a caller owned buffer. The same paths are exercised without `alloc` in `test_core`
+fn test_stack_view() {
+    let mut covariance = [4.0f32, 0.5, 9.0, -0.25, 1.5, 1.0];
+    let mut view = UpperTriViewMut::<_, StcSquare<3>>::from_slice(&mut covariance).unwrap();
+    view.get_diag_mut().for_each(|var| *var += 1.0);
+    *view.get_mut(0, 2).unwrap() = 0.75;
+
+    let view = view.as_view();
+    assert_eq!(view.get_diag().copied().sum::<f32>(), 17.0);
+    assert_eq!(
+        view.strict_upper_iter().argmax(),
+        Some((IndexPair::new(1, 2).unwrap(), &1.5))
+    );
+    assert_eq!(
+        view.sym_row(2).copied().collect::<Vec<_>>(),
+        vec![0.75, 1.5, 2.0]
+    );
+    assert_eq!(offset_of(IndexPair::new(1, 2).unwrap()), 4);
+}
+
+#[test]
+fn test_array_storage() {
+    use crate::upper_tri::upper_tri_array::UpperTriArray;
+    const IDENTITY: UpperTriArray<f64, 3, 6> =
+        UpperTriArray::from_array([1.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+
+    let mut covariance = IDENTITY;
+    let noise = UpperTriArray::<f64, 3, 6>::from_fn(|row, col| if row == col { 0.5 } else { 0.1 });
+    covariance += &noise;
+    covariance[(0, 2)] = -0.2;
+    assert_eq!(IDENTITY[(1, 1)], 1.0);
+    assert_eq!(
+        covariance.get_diag().copied().collect::<Vec<_>>(),
+        vec![1.5; 3]
+    );
+    assert_eq!(
+        covariance.sym_row(2).copied().collect::<Vec<_>>(),
+        vec![-0.2, 0.1, 1.5]
+    );
+
+    let scaled = covariance.map(|v| 2.0 * v);
+    assert_eq!(scaled.into_storage(), vec![3.0, 0.2, 3.0, -0.4, 0.2, 3.0]);
+
+    let heap: UpperTriRawData<f64, StcSquare<3>> = covariance.into();
+    assert_eq!(heap.get(0, 2), Some(&-0.2));
+    let back: UpperTriArray<f64, 3, 6> = heap.into();
+    assert_eq!(back.storage(), covariance.storage());
+}
+
+#[test]
+fn test_static_bordering() {
+    let small = UpperTriRawData::<i32, StcSquare<2>>::from_fn(|row, col| (10 * row + col) as i32);
+    let grown: UpperTriRawData<i32, StcSquare<3>> = small.push_final_col(&[2, 12, 22]);
+    assert_eq!(
+        grown.get_raw_col(2).copied().collect::<Vec<_>>(),
+        vec![2, 12]
+    );
+    assert_eq!(grown.get_diag_el(2), Some(&22));
+
+    let (shrunk, removed): (UpperTriRawData<i32, StcSquare<2>>, _) = grown.drop_at(0);
+    assert_eq!(removed, vec![0, 1, 2]);
+    assert_eq!(shrunk.iter().copied().collect::<Vec<_>>(), vec![11, 12, 22]);
+
+    let bordered = shrunk.insert_at(1, &[5, 6]);
+    assert_eq!(
+        bordered.iter().copied().collect::<Vec<_>>(),
+        vec![11, 5, 6, 12, 0, 22]
+    );
+    assert_eq!(StcSquare::<4>.prev().to_usize(), 3);
+    assert_eq!(DynSquare(0).prev().to_usize(), 0);
+}
+
+#[test]
+fn test_storage_backends() {
+    use crate::storage::PackedStorage;
+    use std::sync::Arc;
+
+    /// stands in for a memory map or a pooled buffer
+    struct Pooled {
+        slot: Vec<f32>,
+    }
+
+    impl PackedStorage<f32> for Pooled {
+        fn as_slice(&self) -> &[f32] {
+            &self.slot
+        }
+    }
+
+    let packed = vec![1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+    let snapshot: Arc<[f32]> = packed.clone().into();
+    let shared = UpperTriRawData::<_, StcSquare<3>, _>::from_packed(snapshot.clone()).unwrap();
+    let reader = shared.clone();
+    assert_eq!(
+        reader.sym_row(1).copied().collect::<Vec<_>>(),
+        vec![2.0, 3.0, 5.0]
+    );
+    assert_eq!(Arc::strong_count(&snapshot), 3);
+    let squares = shared.hadamard(&reader).unwrap();
+    assert_eq!(
+        squares.into_storage(),
+        vec![1.0, 4.0, 9.0, 16.0, 25.0, 36.0]
+    );
+
+    let mut boxed =
+        UpperTriRawData::<_, StcSquare<3>, Box<[f32]>>::from_packed(packed.clone().into()).unwrap();
+    boxed += &shared;
+    boxed.hadamard_inplace(&shared).unwrap();
+    assert_eq!(boxed[(0, 2)], 32.0);
+
+    let mut scratch = packed.clone();
+    let mut borrowed =
+        UpperTriRawData::<_, StcSquare<3>, &mut [f32]>::from_packed(&mut scratch[..]).unwrap();
+    borrowed.swap_indices(0, 2);
+    assert_eq!(scratch, vec![6.0, 5.0, 3.0, 4.0, 2.0, 1.0]);
+
+    let pooled =
+        UpperTriRawData::<_, DynSquare, _>::from_packed(3, Pooled { slot: packed }).unwrap();
+    assert_eq!(pooled.argmax(), Some((IndexPair::new(2, 2).unwrap(), &6.0)));
+    assert_eq!(
+        pooled.map(|v| *v as u8).into_storage(),
+        vec![1, 2, 3, 4, 5, 6]
+    );
+    assert_eq!(
+        UpperTriRawData::<f32, DynSquare, &[f32]>::from_packed(3, &[0.0; 5]).err(),
+        Some(UpperTriError::LengthMismatch {
+            expected: 6,
+            found: 5
+        })
+    );
+}
+
+#[test]
+fn test_triangular_trait() {
+    use crate::upper_tri::permute::permute_symmetric;
+    use crate::upper_tri::reduce::argmax;
+    use crate::upper_tri::submatrix::principal_submatrix;
+    use crate::upper_tri::upper_tri_array::UpperTriArray;
+
+    fn trace<M: TriangularMatrix<f32>>(matrix: &M) -> f32 {
+        matrix.get_diag().sum()
+    }
+
+    fn scale_last_col<M: TriangularMatrixMut<f32>>(matrix: &mut M, by: f32) {
+        let last = matrix.rank() - 1;
+        matrix.col_slice_mut(last).iter_mut().for_each(|t| *t *= by);
+    }
+
+    let packed = vec![1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+    let mut dynamic = UpperTriRawData::<_, DynSquare>::from_packed(3, packed.clone()).unwrap();
+    let mut array = UpperTriArray::<f32, 3, 6>::from_array([1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    let view = UpperTriView::<_, StcSquare<3>>::from_slice(&packed).unwrap();
+    assert_eq!(trace(&dynamic), 10.0);
+    assert_eq!(trace(&array), 10.0);
+    assert_eq!(trace(&view), 10.0);
+    assert_eq!(trace(&dynamic.leading(2).unwrap()), 4.0);
+
+    scale_last_col(&mut dynamic, 2.0);
+    scale_last_col(&mut array.as_view_mut(), 2.0);
+    assert_eq!(dynamic.as_slice(), array.as_slice());
+    assert_eq!(format!("{}", array.as_view()), format!("{}", array));
+
+    let mut stc = UpperTriRawData::<f32, StcSquare<3>>::new();
+    stc += &view;
+    let mut block = stc.as_view_mut();
+    block -= &array;
+    assert_eq!(stc.as_slice(), &[0.0, 0.0, 0.0, -4.0, -5.0, -6.0]);
+    let ratio = stc.zip_map(&view, |s, v| s / v).unwrap();
+    assert_eq!(
+        ratio.get_raw_col(2).copied().collect::<Vec<_>>(),
+        vec![-1.0, -1.0]
+    );
+
+    let sum = view + &array;
+    assert_eq!(sum.as_slice(), &[2.0, 4.0, 6.0, 12.0, 15.0, 18.0]);
+    assert_eq!(argmax(&view), Some((IndexPair::new(2, 2).unwrap(), &6.0)));
+    assert_eq!(
+        permute_symmetric(&view, &[1, 0, 2]).unwrap().as_slice(),
+        &[3.0, 2.0, 1.0, 5.0, 4.0, 6.0]
+    );
+    assert_eq!(
+        principal_submatrix(&view, &[2, 0]).unwrap().as_slice(),
+        &[6.0, 4.0, 1.0]
+    );
+}
+
+#[test]
+fn test_lower_transpose_and_solve() {
+    use crate::upper_tri::lower_tri::LowerTriRawData;
+
+    // U = [[2, 1, 4], [0, 3, 5], [0, 0, 6]]
+    let upper =
+        UpperTriRawData::<f64, DynSquare>::from_packed(3, vec![2.0, 1.0, 3.0, 4.0, 5.0, 6.0])
+            .unwrap();
+    let buf_ptr = upper.as_slice().as_ptr();
+    let lower = upper.transpose();
+    assert_eq!(lower.as_slice().as_ptr(), buf_ptr);
+    assert_eq!(lower[(2, 1)], 5.0);
+    assert_eq!(lower.get(1, 2), None);
+    assert_eq!(lower.row_slice(2), &[4.0, 5.0, 6.0]);
+    assert_eq!(
+        lower.get_raw_col(0).copied().collect::<Vec<_>>(),
+        vec![1.0, 4.0]
+    );
+    assert_eq!(format!("{}", lower), "\n\t2 \n\t1 3 \n\t4 5 6 \n");
+
+    // L x = b for x = (1, 2, 3)
+    let mut b = vec![2.0, 7.0, 32.0];
+    lower.solve_inplace(&mut b).unwrap();
+    assert_eq!(b, vec![1.0, 2.0, 3.0]);
+
+    let upper = lower.transpose();
+    let mut b = vec![16.0, 21.0, 18.0];
+    upper.solve_inplace(&mut b).unwrap();
+    assert_eq!(b, vec![1.0, 2.0, 3.0]);
+    assert_eq!(
+        upper.solve_inplace(&mut [1.0, 2.0]),
+        Err(UpperTriError::LengthMismatch {
+            expected: 3,
+            found: 2
+        })
+    );
+
+    let mut doubled =
+        LowerTriRawData::<f64, StcSquare<2>>::from_fn(|row, col| (row * 2 + col) as f64);
+    let copy = doubled.clone();
+    doubled += &copy;
+    assert_eq!(doubled.as_slice(), &[0.0, 4.0, 6.0]);
+
+    fn last_row_sum<M: LowerTriangular<f64>>(matrix: &M) -> f64 {
+        matrix.row_slice(matrix.rank() - 1).iter().sum()
+    }
+    assert_eq!(last_row_sum(&upper.clone().transpose()), 15.0);
+    assert_eq!(last_row_sum(&doubled), 10.0);
+
+    let squares = doubled.hadamard(&doubled).unwrap();
+    assert_eq!(squares.get(1, 0), Some(&16.0));
+    let ratio = squares.zip_map(&doubled, |s, d| s / d).unwrap();
+    assert_eq!(ratio.map(|r| *r as i32).into_storage(), vec![0, 4, 6]);
+}
+
+#[test]
+fn test_strict_upper() {
+    use crate::upper_tri::strict_upper_tri::StrictUpperTri;
+
+    // distances between points 0, 1, 3 and 6 on a line
+    let points = [0i32, 1, 3, 6];
+    let mut dist =
+        StrictUpperTri::<i32, DynSquare>::from_fn(4, |i, j| (points[j] - points[i]).abs());
+    assert_eq!(dist.as_slice(), &[1, 3, 2, 6, 5, 3]);
+    assert_eq!(dist[(1, 3)], 5);
+    assert_eq!(dist.get(2, 2), None);
+    assert_eq!(dist.get_raw_row(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+    assert_eq!(
+        dist.get_raw_col(3).rev().copied().collect::<Vec<_>>(),
+        vec![3, 5, 6]
+    );
+    assert_eq!(dist.get_raw_row(3).count(), 0);
+    assert_eq!(dist.get_raw_row(usize::MAX).count(), 0);
+    assert_eq!(
+        format!("{}", dist),
+        "\n\t  1 3 6 \n\t    2 5 \n\t      3 \n"
+    );
+
+    let condensed = dist.to_condensed();
+    assert_eq!(condensed, vec![1, 3, 6, 2, 5, 3]);
+    let restored = StrictUpperTri::<i32, DynSquare>::from_condensed(4, &condensed).unwrap();
+    assert_eq!(restored.as_slice(), dist.as_slice());
+    assert!(StrictUpperTri::<i32, DynSquare>::from_packed(4, vec![0; 5]).is_err());
+
+    assert_eq!(dist.drop_at(1), vec![1, 2, 5]);
+    assert_eq!(dist.as_slice(), &[3, 6, 3]);
+    dist.push_final_col(&[10, 7, 4]);
+    assert_eq!(dist[(2, 3)], 4);
+
+    let stc = StrictUpperTri::<i32, StcSquare<3>>::from_packed(vec![1, 2, 3]).unwrap();
+    let grown = stc.push_final_col(&[4, 5, 6]);
+    let (shrunk, removed) = grown.drop_at(0);
+    assert_eq!(removed, vec![1, 2, 4]);
+    assert_eq!(shrunk.as_slice(), &[3, 5, 6]);
+    assert_eq!(shrunk.rank.to_usize(), 3);
+}
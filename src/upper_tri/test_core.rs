@@ -0,0 +1,44 @@
+//! Tests that only build without `alloc`, so the allocator free paths are compiled and run by
+//! `cargo test --no-default-features`
+use crate::dimension::*;
+use crate::upper_tri::upper_tri_array::UpperTriArray;
+use crate::upper_tri::view::*;
+use crate::upper_tri::*;
+
+#[test]
+fn test_view_over_stack_buffer() {
+    let mut covariance = [4.0f32, 0.5, 9.0, -0.25, 1.5, 1.0];
+    let mut view = UpperTriViewMut::<_, StcSquare<3>>::from_slice(&mut covariance).unwrap();
+    view.get_diag_mut().for_each(|var| *var += 1.0);
+    *view.get_mut(0, 2).unwrap() = 0.75;
+
+    let view = view.as_view();
+    assert_eq!(view.get_diag().copied().sum::<f32>(), 17.0);
+    assert_eq!(
+        view.strict_upper_iter().argmax(),
+        Some((IndexPair::new(1, 2).unwrap(), &1.5))
+    );
+    assert!(view.sym_row(2).copied().eq([0.75, 1.5, 2.0]));
+
+    let leading = view.leading(2).unwrap();
+    assert!(leading.columns().flatten().copied().eq([5.0, 0.5, 10.0]));
+    assert!(UpperTriView::<f32, DynSquare>::from_slice(&covariance[..4], 3).is_err());
+}
+
+#[test]
+fn test_array_without_alloc() {
+    const IDENTITY: UpperTriArray<i32, 3, 6> = UpperTriArray::from_array([1, 0, 1, 0, 0, 1]);
+
+    let mut sum = IDENTITY + UpperTriArray::from_fn(|row, col| (10 * row + col) as i32);
+    sum[(0, 2)] = -2;
+    assert_eq!(sum[(1, 1)], 12);
+    assert_eq!(sum.argmax(), Some((IndexPair::new(2, 2).unwrap(), &23)));
+    assert!(sum.get_raw_row(0).copied().eq([1, -2]));
+
+    let mut scratch = [0i32; 6];
+    let mut borrowed =
+        UpperTriRawData::<_, StcSquare<3>, &mut [i32]>::from_packed(&mut scratch[..]).unwrap();
+    borrowed += &sum;
+    borrowed.hadamard_inplace(&IDENTITY).unwrap();
+    assert_eq!(scratch, [1, 0, 12, 0, 0, 23]);
+}
@@ -0,0 +1,221 @@
+use crate::upper_tri::view::UpperTriViewMut;
+use crate::upper_tri::*;
+use core::fmt;
+use core::fmt::Write;
+
+/// Anything holding column packed upper triangular elements: owned matrices with any storage and
+/// borrowed views. Everything is provided through `as_view`, so code taking a
+/// `M: TriangularMatrix<T>` accepts static, dynamic and borrowed matrices alike
+pub trait TriangularMatrix<T> {
+    type Dim: SquareDimension;
+
+    fn as_view(&self) -> UpperTriView<'_, T, Self::Dim>;
+
+    fn dim(&self) -> Self::Dim {
+        self.as_view().rank
+    }
+
+    fn rank(&self) -> usize {
+        self.dim().to_usize()
+    }
+
+    /// The packed elements in column order
+    fn as_slice(&self) -> &[T] {
+        self.as_view().as_slice()
+    }
+
+    fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.as_view().get(row, col)
+    }
+
+    fn get_diag_el(&self, index: usize) -> Option<&T> {
+        self.as_view().get_diag_el(index)
+    }
+
+    fn get_raw_col(&self, col: usize) -> ColView<'_, T> {
+        self.as_view().get_raw_col(col)
+    }
+
+    fn get_raw_row(&self, row: usize) -> RowView<'_, T> {
+        self.as_view().get_raw_row(row)
+    }
+
+    /// All entries of logical row `row` when the matrix is read as symmetric
+    fn sym_row(&self, row: usize) -> CornerView<'_, T> {
+        self.as_view().sym_row(row)
+    }
+
+    fn get_diag(&self) -> DiagView<'_, T> {
+        self.as_view().get_diag()
+    }
+
+    /// Column `col` down to and including the diagonal. Empty if `col` is out of range
+    fn col_slice(&self, col: usize) -> &[T] {
+        self.as_view().col_slice(col)
+    }
+
+    fn columns(&self) -> Columns<'_, T> {
+        self.as_view().columns()
+    }
+
+    fn iter(&self) -> Iter<'_, T> {
+        self.as_view().iter()
+    }
+
+    /// Iterate in packed order together with the position of each element
+    fn indexed_iter(&self) -> IndexedIter<Iter<'_, T>> {
+        self.as_view().indexed_iter()
+    }
+
+    /// Like `indexed_iter` but skipping the diagonal
+    fn strict_upper_iter(&self) -> IndexedIter<Iter<'_, T>> {
+        self.as_view().strict_upper_iter()
+    }
+}
+
+/// A `TriangularMatrix` whose elements can be written in place
+pub trait TriangularMatrixMut<T>: TriangularMatrix<T> {
+    fn as_view_mut(&mut self) -> UpperTriViewMut<'_, T, Self::Dim>;
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self.as_view_mut().into_slice()
+    }
+
+    fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.as_view_mut().into_mut(row, col)
+    }
+
+    fn get_diag_el_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.as_view_mut().into_mut(index, index)
+    }
+
+    fn get_raw_col_mut(&mut self, col: usize) -> ColViewMut<'_, T> {
+        self.as_view_mut().into_raw_col_mut(col)
+    }
+
+    fn get_raw_row_mut(&mut self, row: usize) -> RowViewMut<'_, T> {
+        self.as_view_mut().into_raw_row_mut(row)
+    }
+
+    fn sym_row_mut(&mut self, row: usize) -> CornerViewMut<'_, T> {
+        self.as_view_mut().into_corner_mut(row)
+    }
+
+    fn get_diag_mut(&mut self) -> DiagViewMut<'_, T> {
+        self.as_view_mut().into_diag_mut()
+    }
+
+    fn col_slice_mut(&mut self, col: usize) -> &mut [T] {
+        self.as_view_mut().into_col_slice_mut(col)
+    }
+
+    fn columns_mut(&mut self) -> ColumnsMut<'_, T> {
+        self.as_view_mut().into_columns_mut()
+    }
+
+    fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorage<T>> TriangularMatrix<T> for UpperTriRawData<T, D, S> {
+    type Dim = D;
+
+    fn as_view(&self) -> UpperTriView<'_, T, D> {
+        UpperTriRawData::as_view(self)
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorageMut<T>> TriangularMatrixMut<T>
+    for UpperTriRawData<T, D, S>
+{
+    fn as_view_mut(&mut self) -> UpperTriViewMut<'_, T, D> {
+        UpperTriRawData::as_view_mut(self)
+    }
+}
+
+impl<'a, T, D: SquareDimension> TriangularMatrix<T> for UpperTriView<'a, T, D> {
+    type Dim = D;
+
+    fn as_view(&self) -> UpperTriView<'_, T, D> {
+        *self
+    }
+}
+
+impl<'a, T, D: SquareDimension> TriangularMatrix<T> for UpperTriViewMut<'a, T, D> {
+    type Dim = D;
+
+    fn as_view(&self) -> UpperTriView<'_, T, D> {
+        UpperTriViewMut::as_view(self)
+    }
+}
+
+impl<'a, T, D: SquareDimension> TriangularMatrixMut<T> for UpperTriViewMut<'a, T, D> {
+    fn as_view_mut(&mut self) -> UpperTriViewMut<'_, T, D> {
+        self.reborrow()
+    }
+}
+
+/// Counts the characters an element prints as, so widths can be found without allocating
+struct Width(usize);
+
+impl Write for Width {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.chars().count();
+        Ok(())
+    }
+}
+
+/// The printed width of the widest element, or 1 for an empty matrix
+pub(crate) fn element_width<'a, T, I>(elements: I) -> Result<usize, fmt::Error>
+where
+    T: fmt::Display + 'a,
+    I: IntoIterator<Item = &'a T>,
+{
+    let mut width = None;
+    for t in elements {
+        let mut counter = Width(0);
+        write!(counter, "{}", t)?;
+        width = width.max(Some(counter.0));
+    }
+    Ok(width.unwrap_or(1))
+}
+
+/// The upper triangle laid out in rows, with elements padded to the width of the widest one so
+/// the columns line up. Shared by the `Display` impls of the owned and borrowed matrices
+pub(crate) fn fmt_triangle<T, M>(matrix: &M, f: &mut fmt::Formatter<'_>) -> fmt::Result
+where
+    T: fmt::Display,
+    M: TriangularMatrix<T> + ?Sized,
+{
+    let width = element_width(matrix.iter())?;
+    writeln!(f)?;
+    for row in 0..matrix.rank() {
+        write!(f, "\t{:indent$}", "", indent = (width + 1) * row)?;
+        let diag = matrix.get_diag_el(row).ok_or(fmt::Error)?;
+        write!(f, "{:>width$} ", diag, width = width)?;
+        for t in matrix.get_raw_row(row) {
+            write!(f, "{:>width$} ", t, width = width)?;
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+/// The lower triangle of the transpose of `upper`, so each printed row is a column of `upper`
+pub(crate) fn fmt_transposed_triangle<T, M>(upper: &M, f: &mut fmt::Formatter<'_>) -> fmt::Result
+where
+    T: fmt::Display,
+    M: TriangularMatrix<T> + ?Sized,
+{
+    let width = element_width(upper.iter())?;
+    writeln!(f)?;
+    for col in 0..upper.rank() {
+        write!(f, "\t")?;
+        for t in upper.col_slice(col) {
+            write!(f, "{:>width$} ", t, width = width)?;
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
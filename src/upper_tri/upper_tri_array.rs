@@ -0,0 +1,88 @@
+use crate::dimension::StcSquare;
+use crate::upper_tri::*;
+#[cfg(feature = "alloc")]
+use core::convert::TryFrom;
+
+/// A static matrix stored inline in a column packed array, so it lives on the stack and is `Copy`
+/// whenever `T` is. `L` is the packed length and must be N * (N + 1) / 2; this is checked when
+/// the type is used, since stable Rust can't compute it from `N` in the type
+pub type UpperTriArray<T, const N: usize, const L: usize> =
+    UpperTriRawData<T, StcSquare<N>, [T; L]>;
+
+impl<T, const N: usize, const L: usize> UpperTriArray<T, N, L> {
+    const PACKED_LEN: usize = {
+        assert!(
+            L == N * (N + 1) / 2,
+            "packed length must be N * (N + 1) / 2"
+        );
+        L
+    };
+
+    /// Take ownership of a column packed array. Unlike `from_packed` the length is checked at
+    /// compile time
+    pub const fn from_array(buf: [T; L]) -> Self {
+        let _ = Self::PACKED_LEN;
+        Self::from_parts(buf, StcSquare)
+    }
+
+    /// Build a matrix from the value at each position on or above the diagonal
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(mut f: F) -> Self {
+        Self::from_array(core::array::from_fn(|offset| {
+            let pair = pair_of(offset);
+            f(pair.row, pair.col)
+        }))
+    }
+}
+
+impl<T: Copy, const N: usize, const L: usize> UpperTriArray<T, N, L> {
+    /// Every element set to `t`
+    pub const fn new_with(t: T) -> Self {
+        Self::from_array([t; L])
+    }
+}
+
+impl<T: Zero, const N: usize, const L: usize> UpperTriArray<T, N, L> {
+    pub fn new() -> Self {
+        Self::from_array(core::array::from_fn(|_| T::zero()))
+    }
+}
+
+impl<T: Zero, const N: usize, const L: usize> Default for UpperTriArray<T, N, L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize, const L: usize> Add<UpperTriArray<T, N, L>> for UpperTriArray<T, N, L>
+where
+    T: for<'b> AddAssign<&'b T>,
+{
+    type Output = UpperTriArray<T, N, L>;
+
+    fn add(mut self, rhs: UpperTriArray<T, N, L>) -> Self::Output {
+        self += &rhs;
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const N: usize, const L: usize> From<UpperTriArray<T, N, L>>
+    for UpperTriRawData<T, StcSquare<N>>
+{
+    fn from(array: UpperTriArray<T, N, L>) -> Self {
+        UpperTriRawData::from_parts(Vec::from(array.buf), StcSquare)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const N: usize, const L: usize> From<UpperTriRawData<T, StcSquare<N>>>
+    for UpperTriArray<T, N, L>
+{
+    fn from(stc: UpperTriRawData<T, StcSquare<N>>) -> Self {
+        let _ = Self::PACKED_LEN;
+        match <[T; L]>::try_from(stc.buf) {
+            Ok(buf) => Self::from_array(buf),
+            Err(_) => unreachable!("a static matrix always holds N * (N + 1) / 2 elements"),
+        }
+    }
+}
@@ -1,18 +1,78 @@
 use crate::upper_tri::*;
+use core::iter::FromIterator;
+
+impl<T> UpperTriRawData<T, DynSquare> {
+    /// Build a matrix from the value at each position on or above the diagonal
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(rank: usize, f: F) -> Self {
+        Self::from_parts(packed_from_fn(rank, f), DynSquare(rank))
+    }
+
+    /// An empty matrix with room to grow up to `rank` without reallocating
+    pub fn with_capacity(rank: usize) -> Self {
+        Self::from_parts(Vec::with_capacity(rank * (rank + 1) / 2), DynSquare(0))
+    }
+
+    /// Keep only the leading `new_rank` rows and columns. Does nothing if `new_rank` is not
+    /// smaller than the current rank
+    pub fn truncate(&mut self, new_rank: usize) {
+        if new_rank < self.rank.0 {
+            self.buf.truncate(new_rank * (new_rank + 1) / 2);
+            self.rank = DynSquare(new_rank);
+        }
+    }
+
+    pub fn drop_at(&mut self, index: usize) -> Vec<T> {
+        assert!(
+            index < self.rank.0,
+            "drop index {} out of range for rank {}",
+            index,
+            self.rank.0
+        );
+        let mut return_vec = Vec::with_capacity(self.rank.0);
+        let col_offset = index * (index + 1) / 2;
+        let col_end = col_offset + index + 1;
+        let tmp = self.buf.drain(Range {
+            start: col_offset,
+            end: col_end,
+        });
+
+        return_vec.extend(tmp);
+        // by the time we reach `col` we have removed `index + 1` elements from the dropped column
+        // and one from each of the `col - index - 1` columns before it
+        for col in (index + 1)..self.rank.0 {
+            let next_to_remove = offset_for_col(col, index) - col;
+            let t = self.buf.remove(next_to_remove);
+            return_vec.push(t);
+        }
+        self.rank.shrink();
+        return_vec
+    }
+}
 
 impl<T> UpperTriRawData<T, DynSquare>
 where
-    T: Copy + Zero,
+    T: Zero,
 {
     pub fn new(rank: usize) -> Self {
-        let def = T::zero();
-        let buf = repeat(def).take(rank * (rank + 1) / 2).collect();
-        Self {
-            buf,
-            rank: DynSquare(rank),
-        }
+        let buf = repeat_with(T::zero).take(rank * (rank + 1) / 2).collect();
+        Self::from_parts(buf, DynSquare(rank))
     }
 
+    pub fn push_final_col_iter_owned<Itr: Iterator<Item = T>>(&mut self, iter: Itr) {
+        let new = self.rank.to_usize() + 1;
+
+        let new_iter = iter.chain(repeat_with(T::zero)).take(new);
+
+        self.buf.reserve(new); //Could probably bypass this if we can assure that new iter has trusted_len
+        self.buf.extend(new_iter);
+        self.rank.grow();
+    }
+}
+
+impl<T> UpperTriRawData<T, DynSquare>
+where
+    T: Clone + Zero,
+{
     pub fn push_final_col_iter<DerefT: Deref<Target = T>, Itr: Iterator<Item = DerefT>>(
         &mut self,
         iter: Itr,
@@ -20,54 +80,183 @@ where
         let rank = self.rank.to_usize();
         let new = rank + 1;
 
-        let default = T::zero();
-        let new_iter = iter.map(|x| *x).chain(repeat(default)).take(new);
+        let new_iter = iter
+            .map(|x| x.deref().clone())
+            .chain(repeat_with(T::zero))
+            .take(new);
 
         self.buf.reserve(new); //Could probably bypass this if we can assure that new iter has trusted_len
         self.buf.extend(new_iter);
         self.rank.grow();
     }
 
-    pub fn push_final_col_iter_owned<Itr: Iterator<Item = T>>(&mut self, iter: Itr) {
-        let new = self.rank.to_usize() + 1;
+    pub fn push_final_col(&mut self, vec: &[T]) {
+        self.push_final_col_iter(vec.iter())
+    }
 
-        let default = T::zero();
-        let new_iter = iter.map(|x| x).chain(repeat(default)).take(new);
+    /// Insert a new row and column at `index`, shifting later ones along. `values` uses the
+    /// layout returned by `drop_at`: the new column down to the diagonal followed by the rest of
+    /// the new row, so `insert_at(i, &drop_at(i))` restores the original. Missing values are
+    /// filled with zero
+    pub fn insert_at(&mut self, index: usize, values: &[T]) {
+        let rank = self.rank.to_usize();
+        assert!(
+            index <= rank,
+            "insert index {} out of range for rank {}",
+            index,
+            rank
+        );
+        let new = rank + 1;
+        let mut old =
+            core::mem::replace(&mut self.buf, Vec::with_capacity(new * (new + 1) / 2)).into_iter();
+        let mut values = values.iter().cloned().chain(repeat_with(T::zero));
 
-        self.buf.reserve(new); //Could probably bypass this if we can assure that new iter has trusted_len
-        self.buf.extend(new_iter);
+        self.buf.extend(old.by_ref().take(offset_for_col(index, 0)));
+        self.buf.extend(values.by_ref().take(index + 1));
+        for col in index..rank {
+            self.buf.extend(old.by_ref().take(index));
+            self.buf.extend(values.next());
+            self.buf.extend(old.by_ref().take(col + 1 - index));
+        }
         self.rank.grow();
     }
 
-    pub fn push_final_col(&mut self, vec: &[T]) {
-        self.push_final_col_iter(vec.iter())
+    /// The block diagonal matrix `self ⊕ other` with a zero cross block
+    pub fn direct_sum(&self, other: &Self) -> Self {
+        self.direct_sum_with(other, T::zero())
     }
+}
 
-    pub fn drop_at(&mut self, index: usize) -> Vec<T> {
-        let mut return_vec = Vec::with_capacity(self.rank.0);
-        let col_offset = index * (index + 1) / 2;
-        let col_end = col_offset + index + 1;
-        let tmp = self.buf.drain(Range {
-            start: col_offset,
-            end: col_end,
-        });
+impl<T> UpperTriRawData<T, DynSquare>
+where
+    T: Clone,
+{
+    pub fn resize(&mut self, new_rank: usize, fill: T) {
+        self.buf.resize(new_rank * (new_rank + 1) / 2, fill);
+        self.rank = DynSquare(new_rank);
+    }
 
-        return_vec.extend(tmp);
-        let mut removed = index;
-        for col in (index + 1)..self.rank.0 {
-            let next_to_remove = offset_for_col(col, index) - removed;
-            let t = self.buf.remove(next_to_remove);
-            return_vec.push(t);
-            removed += 1;
+    /// Remove every row and column at the given indices in a single pass over the buffer.
+    /// Returns, in increasing index order, what `drop_at` would have returned for each index on
+    /// the original matrix
+    pub fn drop_many(&mut self, indices: &[usize]) -> Vec<Vec<T>> {
+        let rank = self.rank.to_usize();
+        let mut keep = vec![true; rank];
+        for &index in indices {
+            assert!(
+                index < rank,
+                "drop index {} out of range for rank {}",
+                index,
+                rank
+            );
+            keep[index] = false;
         }
-        self.rank.shrink();
-        return_vec
+        self.retain_indices(|index| keep[index])
+    }
+
+    /// Keep only the rows and columns whose index satisfies `keep`, compacting the buffer in a
+    /// single pass. The removed rows and columns are returned as in `drop_many`
+    pub fn retain_indices<F: FnMut(usize) -> bool>(&mut self, mut keep: F) -> Vec<Vec<T>> {
+        let rank = self.rank.to_usize();
+        let kept = (0..rank).map(&mut keep).collect::<Vec<_>>();
+        // position of each removed index in the returned vector
+        let mut slots = vec![None; rank];
+        let mut removed = Vec::new();
+        for (index, _) in kept.iter().enumerate().filter(|(_, k)| !**k) {
+            slots[index] = Some(removed.len());
+            removed.push(Vec::with_capacity(rank));
+        }
+
+        let mut write = 0;
+        let mut read = 0;
+        for col in 0..rank {
+            for row in 0..=col {
+                if kept[row] && kept[col] {
+                    // everything before `read` has been visited, so whatever is swapped back is
+                    // either already copied out or dropped by the final truncate
+                    self.buf.swap(write, read);
+                    write += 1;
+                } else {
+                    if let Some(slot) = slots[col] {
+                        removed[slot].push(self.buf[read].clone());
+                    }
+                    if let Some(slot) = slots[row].filter(|_| row != col) {
+                        removed[slot].push(self.buf[read].clone());
+                    }
+                }
+                read += 1;
+            }
+        }
+        self.buf.truncate(write);
+        self.rank = DynSquare(rank - removed.len());
+        removed
+    }
+
+    /// Place `other` after `self` along the diagonal. The block of pairs between the two is taken
+    /// column by column from `cross`
+    fn concat_with<Itr: Iterator<Item = T>>(&self, other: &Self, mut cross: Itr) -> Self {
+        let left = self.rank.to_usize();
+        let right = other.rank.to_usize();
+        let new = left + right;
+        let mut buf = Vec::with_capacity(new * (new + 1) / 2);
+        buf.extend_from_slice(&self.buf);
+        for col in 0..right {
+            buf.extend(cross.by_ref().take(left));
+            let col_offset = offset_for_col(col, 0);
+            buf.extend_from_slice(&other.buf[col_offset..=(col_offset + col)]);
+        }
+        UpperTriRawData::from_parts(buf, DynSquare(new))
+    }
+
+    /// The block diagonal matrix `self ⊕ other` with every cross element set to `fill`
+    pub fn direct_sum_with(&self, other: &Self, fill: T) -> Self {
+        self.concat_with(other, repeat(fill))
+    }
+
+    /// Join two matrices with the dense `left.rank × right.rank` block of pairs between them.
+    /// `cross` is column major, so the pair of `left` index `i` and `right` index `j` is at
+    /// `cross[j * left.rank + i]`
+    pub fn join(left: &Self, right: &Self, cross: &[T]) -> Result<Self, UpperTriError> {
+        let expected = left.rank.to_usize() * right.rank.to_usize();
+        if cross.len() != expected {
+            return Err(UpperTriError::LengthMismatch {
+                expected,
+                found: cross.len(),
+            });
+        }
+        Ok(left.concat_with(right, cross.iter().cloned()))
     }
 }
 
-impl<'a, 'b, T> Add<&'b UpperTriRawData<T, DynSquare>> for &'a UpperTriRawData<T, DynSquare>
+/// Collects columns, each padded or cut to length like `push_final_col`
+impl<T, C> FromIterator<C> for UpperTriRawData<T, DynSquare>
 where
-    T: Copy + Zero + for<'c> AddAssign<&'c T>,
+    T: Zero,
+    C: IntoIterator<Item = T>,
+{
+    fn from_iter<I: IntoIterator<Item = C>>(columns: I) -> Self {
+        let mut upper_tri = Self::with_capacity(0);
+        upper_tri.extend(columns);
+        upper_tri
+    }
+}
+
+/// Appends columns like `push_final_col`
+impl<T, C> Extend<C> for UpperTriRawData<T, DynSquare>
+where
+    T: Zero,
+    C: IntoIterator<Item = T>,
+{
+    fn extend<I: IntoIterator<Item = C>>(&mut self, columns: I) {
+        columns
+            .into_iter()
+            .for_each(|column| self.push_final_col_iter_owned(column.into_iter()));
+    }
+}
+
+impl<'b, T> Add<&'b UpperTriRawData<T, DynSquare>> for &UpperTriRawData<T, DynSquare>
+where
+    T: Clone + for<'c> AddAssign<&'c T>,
 {
     type Output = UpperTriRawData<T, DynSquare>;
 
@@ -81,10 +270,7 @@ where
                     *return_val += new;
                 });
 
-            UpperTriRawData {
-                buf: return_buf,
-                rank: rhs.rank,
-            }
+            UpperTriRawData::from_parts(return_buf, rhs.rank)
         } else {
             let mut return_buf = self.buf.clone();
             return_buf
@@ -93,10 +279,7 @@ where
                 .for_each(|(return_val, new)| {
                     *return_val += new;
                 });
-            UpperTriRawData {
-                buf: return_buf,
-                rank: self.rank,
-            }
+            UpperTriRawData::from_parts(return_buf, self.rank)
         }
     }
 }
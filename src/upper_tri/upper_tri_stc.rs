@@ -1,54 +1,149 @@
+use crate::dimension::HasNext;
+use crate::dimension::HasPrev;
+use crate::dimension::StaticDimension;
 use crate::dimension::StcSquare;
 use crate::upper_tri::*;
+use core::convert::TryFrom;
 
-impl<T, const N: usize> UpperTriRawData<T, StcSquare<N>>
+impl<T, D: StaticDimension> UpperTriRawData<T, D> {
+    /// Build a matrix from the value at each position on or above the diagonal
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(f: F) -> Self {
+        Self::from_parts(packed_from_fn(D::RANK, f), D::DIM)
+    }
+}
+
+impl<T, D: StaticDimension> UpperTriRawData<T, D>
 where
-    T: Copy + Zero,
+    T: Zero,
 {
     pub fn new() -> Self {
-        let def = T::zero();
-        let buf = repeat(def).take(N * (N + 1) / 2).collect();
-        Self {
-            buf,
-            rank: StcSquare,
-        }
+        let buf = repeat_with(T::zero)
+            .take(D::RANK * (D::RANK + 1) / 2)
+            .collect();
+        Self::from_parts(buf, D::DIM)
     }
 }
 
-impl<T, const N: usize> UpperTriRawData<T, StcSquare<N>>
+impl<T, D: StaticDimension> Default for UpperTriRawData<T, D>
 where
-    T: Copy,
+    T: Zero,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, D: StaticDimension> UpperTriRawData<T, D>
+where
+    T: Clone,
 {
     pub fn new_with(t: T) -> Self {
-        let buf = repeat(t).take(N * (N + 1) / 2).collect();
-        Self {
-            buf,
-            rank: StcSquare,
+        let buf = repeat_n(t, D::RANK * (D::RANK + 1) / 2).collect();
+        Self::from_parts(buf, D::DIM)
+    }
+}
+
+impl<T, const N: usize> From<UpperTriRawData<T, StcSquare<N>>> for UpperTriRawData<T, DynSquare> {
+    fn from(stc: UpperTriRawData<T, StcSquare<N>>) -> Self {
+        UpperTriRawData::from_parts(stc.buf, DynSquare(N))
+    }
+}
+
+/// Fails if the rank of the dynamic matrix is not `N`
+impl<T, const N: usize> TryFrom<UpperTriRawData<T, DynSquare>>
+    for UpperTriRawData<T, StcSquare<N>>
+{
+    type Error = UpperTriError;
+
+    fn try_from(dyn_data: UpperTriRawData<T, DynSquare>) -> Result<Self, Self::Error> {
+        if dyn_data.rank.to_usize() != N {
+            return Err(UpperTriError::RankMismatch {
+                expected: N,
+                found: dyn_data.rank.to_usize(),
+            });
         }
+        Ok(UpperTriRawData::from_parts(dyn_data.buf, StcSquare))
+    }
+}
+
+impl<T, D: StaticDimension> UpperTriRawData<T, D> {
+    /// Run a rank changing operation on the dynamic form, then label the result with the static
+    /// rank it is known to end up with
+    fn resize_via_dyn<E: SquareDimension, R, F>(self, rank: E, f: F) -> (UpperTriRawData<T, E>, R)
+    where
+        F: FnOnce(&mut UpperTriRawData<T, DynSquare>) -> R,
+    {
+        let mut dyn_data = UpperTriRawData::from_parts(self.buf, DynSquare(D::RANK));
+        let out = f(&mut dyn_data);
+        debug_assert_eq!(dyn_data.rank.to_usize(), rank.to_usize());
+        let resized = UpperTriRawData::from_parts(dyn_data.buf, rank);
+        (resized, out)
+    }
+}
+
+/// Bordering operations. These consume the matrix since the static rank changes
+impl<T, D: StaticDimension> UpperTriRawData<T, D>
+where
+    D: HasNext,
+    T: Zero,
+{
+    pub fn push_final_col_iter_owned<Itr: Iterator<Item = T>>(
+        self,
+        iter: Itr,
+    ) -> UpperTriRawData<T, D::Next> {
+        let rank = self.rank.next();
+        self.resize_via_dyn(rank, |grown| grown.push_final_col_iter_owned(iter))
+            .0
+    }
+}
+
+impl<T, D: StaticDimension> UpperTriRawData<T, D>
+where
+    D: HasNext,
+    T: Clone + Zero,
+{
+    pub fn push_final_col(self, vec: &[T]) -> UpperTriRawData<T, D::Next> {
+        let rank = self.rank.next();
+        self.resize_via_dyn(rank, |grown| grown.push_final_col(vec))
+            .0
+    }
+
+    /// See the dynamic `insert_at`
+    pub fn insert_at(self, index: usize, values: &[T]) -> UpperTriRawData<T, D::Next> {
+        let rank = self.rank.next();
+        self.resize_via_dyn(rank, |grown| grown.insert_at(index, values))
+            .0
+    }
+}
+
+impl<T, D: StaticDimension> UpperTriRawData<T, D>
+where
+    D: HasPrev,
+{
+    /// Remove row and column `index`, returning the smaller matrix and the removed elements in
+    /// the layout of the dynamic `drop_at`
+    pub fn drop_at(self, index: usize) -> (UpperTriRawData<T, D::Prev>, Vec<T>) {
+        assert!(
+            index < D::RANK,
+            "drop index {} out of range for rank {}",
+            index,
+            D::RANK
+        );
+        let rank = self.rank.prev();
+        self.resize_via_dyn(rank, |shrunk| shrunk.drop_at(index))
     }
 }
 
 /// For the static implementation we are guaranteed that the sizes are the same so we don't need a
 /// Zero bound on T
-impl<'a, 'b, T, const N: usize> Add<&'b UpperTriRawData<T, StcSquare<N>>>
-    for &'a UpperTriRawData<T, StcSquare<N>>
+impl<'b, T, const N: usize> Add<&'b UpperTriRawData<T, StcSquare<N>>>
+    for &UpperTriRawData<T, StcSquare<N>>
 where
-    T: Copy + for<'c> AddAssign<&'c T>,
+    T: Clone + for<'c> AddAssign<&'c T>,
 {
     type Output = UpperTriRawData<T, StcSquare<N>>;
 
     fn add(self, rhs: &'b UpperTriRawData<T, StcSquare<N>>) -> Self::Output {
-        let mut return_buf = rhs.buf.clone();
-        return_buf
-            .iter_mut()
-            .zip(self.buf.iter())
-            .for_each(|(return_val, new)| {
-                *return_val += new;
-            });
-
-        UpperTriRawData {
-            buf: return_buf,
-            rank: StcSquare,
-        }
+        super::add(self, rhs)
     }
 }
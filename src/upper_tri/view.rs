@@ -0,0 +1,419 @@
+use crate::dimension::StcSquare;
+use crate::upper_tri::*;
+
+/// A borrowed upper triangular matrix over a column packed slice. Since the leading k×k block of
+/// a packed matrix is the first k(k+1)/2 elements of its buffer, these can be taken from an
+/// existing matrix without copying
+pub struct UpperTriView<'a, T, D: SquareDimension> {
+    buf: &'a [T],
+    pub rank: D,
+}
+
+// derived impls would require T: Copy
+impl<'a, T, D: SquareDimension> Clone for UpperTriView<'a, T, D> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, D: SquareDimension> Copy for UpperTriView<'a, T, D> {}
+
+/// A mutably borrowed upper triangular matrix over a column packed slice
+pub struct UpperTriViewMut<'a, T, D: SquareDimension> {
+    buf: &'a mut [T],
+    pub rank: D,
+}
+
+fn offset_in_rank(rank: usize, row: usize, col: usize) -> Option<usize> {
+    if row > col || col >= rank {
+        None
+    } else {
+        Some(offset_for_col(col, row))
+    }
+}
+
+impl<'a, T> UpperTriView<'a, T, DynSquare> {
+    pub fn from_slice(buf: &'a [T], rank: usize) -> Result<Self, UpperTriError> {
+        check_packed_len(buf.len(), rank)?;
+        Ok(UpperTriView {
+            buf,
+            rank: DynSquare(rank),
+        })
+    }
+}
+
+impl<'a, T, const N: usize> UpperTriView<'a, T, StcSquare<N>> {
+    pub fn from_slice(buf: &'a [T]) -> Result<Self, UpperTriError> {
+        check_packed_len(buf.len(), N)?;
+        Ok(UpperTriView {
+            buf,
+            rank: StcSquare,
+        })
+    }
+}
+
+impl<'a, T> UpperTriViewMut<'a, T, DynSquare> {
+    pub fn from_slice(buf: &'a mut [T], rank: usize) -> Result<Self, UpperTriError> {
+        check_packed_len(buf.len(), rank)?;
+        Ok(UpperTriViewMut {
+            buf,
+            rank: DynSquare(rank),
+        })
+    }
+}
+
+impl<'a, T, const N: usize> UpperTriViewMut<'a, T, StcSquare<N>> {
+    pub fn from_slice(buf: &'a mut [T]) -> Result<Self, UpperTriError> {
+        check_packed_len(buf.len(), N)?;
+        Ok(UpperTriViewMut {
+            buf,
+            rank: StcSquare,
+        })
+    }
+}
+
+impl<'a, T, D: SquareDimension> UpperTriView<'a, T, D> {
+    /// The packed elements in column order
+    pub fn as_slice(&self) -> &'a [T] {
+        self.buf
+    }
+
+    /// The leading `rank`×`rank` principal submatrix
+    pub fn leading(&self, rank: usize) -> Result<UpperTriView<'a, T, DynSquare>, UpperTriError> {
+        if rank > self.rank.to_usize() {
+            return Err(UpperTriError::IndexOutOfRange {
+                index: rank,
+                rank: self.rank.to_usize(),
+            });
+        }
+        UpperTriView::<T, DynSquare>::from_slice(&self.buf[..(rank * (rank + 1) / 2)], rank)
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&'a T> {
+        let offset = offset_in_rank(self.rank.to_usize(), row, col)?;
+        self.buf.get(offset)
+    }
+
+    pub fn get_diag_el(&self, index: usize) -> Option<&'a T> {
+        self.get(index, index)
+    }
+
+    pub fn get_raw_col(&self, col: usize) -> ColView<'a, T> {
+        ColView::new(self.buf, self.rank.to_usize(), col)
+    }
+
+    pub fn get_raw_row(&self, row: usize) -> RowView<'a, T> {
+        RowView::new(self.buf, self.rank.to_usize(), row)
+    }
+
+    pub fn get_corner(&self, diagonal_element: usize) -> CornerView<'a, T> {
+        CornerView::new(self.buf, self.rank.to_usize(), diagonal_element)
+    }
+
+    /// All entries of logical row `row` when the matrix is read as symmetric. This is the same
+    /// as the corner at `row`
+    pub fn sym_row(&self, row: usize) -> CornerView<'a, T> {
+        self.get_corner(row)
+    }
+
+    /// All entries of logical row `row` when the matrix is read as skew symmetric
+    pub fn skew_row(&self, row: usize) -> SkewRowView<'a, T> {
+        SkewRowView::new(self.buf, self.rank.to_usize(), row)
+    }
+
+    pub fn get_diag(&self) -> DiagView<'a, T> {
+        DiagView::new(self.buf, self.rank.to_usize())
+    }
+
+    /// The elements (i, i + `offset`)
+    pub fn get_superdiag(&self, offset: usize) -> SuperDiagView<'a, T> {
+        SuperDiagView::new(self.buf, self.rank.to_usize(), offset)
+    }
+
+    pub fn iter_by_superdiag(&self) -> SuperDiagOrder<'a, T, D> {
+        SuperDiagOrder {
+            view: *self,
+            row: 0,
+            offset: 0,
+        }
+    }
+
+    /// Column `col` down to and including the diagonal. Empty if `col` is out of range
+    pub fn col_slice(&self, col: usize) -> &'a [T] {
+        &self.buf[col_slice_range(self.rank.to_usize(), col)]
+    }
+
+    pub fn columns(&self) -> Columns<'a, T> {
+        Columns::new(self.buf, self.rank.to_usize())
+    }
+
+    pub fn iter(&self) -> Iter<'a, T> {
+        self.buf.iter()
+    }
+
+    pub fn indexed_iter(&self) -> IndexedIter<Iter<'a, T>> {
+        IndexedIter::new(self.buf.iter(), self.rank.to_usize(), false)
+    }
+
+    pub fn strict_upper_iter(&self) -> IndexedIter<Iter<'a, T>> {
+        IndexedIter::new(self.buf.iter(), self.rank.to_usize(), true)
+    }
+}
+
+impl<'a, T, D: SquareDimension> UpperTriViewMut<'a, T, D> {
+    pub fn as_view(&self) -> UpperTriView<'_, T, D> {
+        UpperTriView {
+            buf: self.buf,
+            rank: self.rank,
+        }
+    }
+
+    /// A shorter lived mutable view of the same elements
+    pub fn reborrow(&mut self) -> UpperTriViewMut<'_, T, D> {
+        UpperTriViewMut {
+            buf: self.buf,
+            rank: self.rank,
+        }
+    }
+
+    pub fn into_slice(self) -> &'a mut [T] {
+        self.buf
+    }
+
+    /// The leading `rank`×`rank` principal submatrix
+    pub fn into_leading(
+        self,
+        rank: usize,
+    ) -> Result<UpperTriViewMut<'a, T, DynSquare>, UpperTriError> {
+        if rank > self.rank.to_usize() {
+            return Err(UpperTriError::IndexOutOfRange {
+                index: rank,
+                rank: self.rank.to_usize(),
+            });
+        }
+        UpperTriViewMut::<T, DynSquare>::from_slice(&mut self.buf[..(rank * (rank + 1) / 2)], rank)
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        let offset = offset_in_rank(self.rank.to_usize(), row, col)?;
+        self.buf.get(offset)
+    }
+
+    pub fn get_diag_el(&self, index: usize) -> Option<&T> {
+        self.get(index, index)
+    }
+
+    pub fn get_raw_col(&self, col: usize) -> ColView<'_, T> {
+        self.as_view().get_raw_col(col)
+    }
+
+    pub fn get_raw_row(&self, row: usize) -> RowView<'_, T> {
+        self.as_view().get_raw_row(row)
+    }
+
+    pub fn get_corner(&self, diagonal_element: usize) -> CornerView<'_, T> {
+        self.as_view().get_corner(diagonal_element)
+    }
+
+    pub fn get_diag(&self) -> DiagView<'_, T> {
+        self.as_view().get_diag()
+    }
+
+    pub fn sym_row(&self, row: usize) -> CornerView<'_, T> {
+        self.as_view().sym_row(row)
+    }
+
+    pub fn skew_row(&self, row: usize) -> SkewRowView<'_, T> {
+        self.as_view().skew_row(row)
+    }
+
+    pub fn get_superdiag(&self, offset: usize) -> SuperDiagView<'_, T> {
+        self.as_view().get_superdiag(offset)
+    }
+
+    pub fn iter_by_superdiag(&self) -> SuperDiagOrder<'_, T, D> {
+        self.as_view().iter_by_superdiag()
+    }
+
+    pub fn col_slice(&self, col: usize) -> &[T] {
+        self.as_view().col_slice(col)
+    }
+
+    pub fn columns(&self) -> Columns<'_, T> {
+        self.as_view().columns()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.buf.iter()
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.reborrow().into_mut(row, col)
+    }
+
+    pub fn get_diag_el_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.get_mut(index, index)
+    }
+
+    pub fn get_raw_col_mut(&mut self, col: usize) -> ColViewMut<'_, T> {
+        self.reborrow().into_raw_col_mut(col)
+    }
+
+    pub fn get_raw_row_mut(&mut self, row: usize) -> RowViewMut<'_, T> {
+        self.reborrow().into_raw_row_mut(row)
+    }
+
+    pub fn get_corner_mut(&mut self, diagonal_element: usize) -> CornerViewMut<'_, T> {
+        self.reborrow().into_corner_mut(diagonal_element)
+    }
+
+    pub fn sym_row_mut(&mut self, row: usize) -> CornerViewMut<'_, T> {
+        self.get_corner_mut(row)
+    }
+
+    pub fn get_diag_mut(&mut self) -> DiagViewMut<'_, T> {
+        self.reborrow().into_diag_mut()
+    }
+
+    pub fn get_superdiag_mut(&mut self, offset: usize) -> SuperDiagViewMut<'_, T> {
+        self.reborrow().into_superdiag_mut(offset)
+    }
+
+    pub fn col_slice_mut(&mut self, col: usize) -> &mut [T] {
+        self.reborrow().into_col_slice_mut(col)
+    }
+
+    pub fn columns_mut(&mut self) -> ColumnsMut<'_, T> {
+        self.reborrow().into_columns_mut()
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.buf.iter_mut()
+    }
+
+    pub fn indexed_iter_mut(&mut self) -> IndexedIter<IterMut<'_, T>> {
+        IndexedIter::new(self.buf.iter_mut(), self.rank.to_usize(), false)
+    }
+
+    pub fn strict_upper_iter_mut(&mut self) -> IndexedIter<IterMut<'_, T>> {
+        IndexedIter::new(self.buf.iter_mut(), self.rank.to_usize(), true)
+    }
+
+    pub fn into_col_slice_mut(self, col: usize) -> &'a mut [T] {
+        let range = col_slice_range(self.rank.to_usize(), col);
+        &mut self.buf[range]
+    }
+
+    pub fn into_columns_mut(self) -> ColumnsMut<'a, T> {
+        ColumnsMut::new(self.buf, self.rank.to_usize())
+    }
+
+    pub fn into_mut(self, row: usize, col: usize) -> Option<&'a mut T> {
+        let offset = offset_in_rank(self.rank.to_usize(), row, col)?;
+        self.buf.get_mut(offset)
+    }
+
+    pub fn into_raw_col_mut(self, col: usize) -> ColViewMut<'a, T> {
+        ColViewMut::new(self.buf, self.rank.to_usize(), col)
+    }
+
+    pub fn into_raw_row_mut(self, row: usize) -> RowViewMut<'a, T> {
+        RowViewMut::new(self.buf, self.rank.to_usize(), row)
+    }
+
+    pub fn into_corner_mut(self, diagonal_element: usize) -> CornerViewMut<'a, T> {
+        CornerViewMut::new(self.buf, self.rank.to_usize(), diagonal_element)
+    }
+
+    pub fn into_diag_mut(self) -> DiagViewMut<'a, T> {
+        DiagViewMut::new(self.buf, self.rank.to_usize())
+    }
+
+    pub fn into_superdiag_mut(self, offset: usize) -> SuperDiagViewMut<'a, T> {
+        SuperDiagViewMut::new(self.buf, self.rank.to_usize(), offset)
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorage<T>> UpperTriRawData<T, D, S> {
+    pub fn as_view(&self) -> UpperTriView<'_, T, D> {
+        UpperTriView {
+            buf: self.buf.as_slice(),
+            rank: self.rank,
+        }
+    }
+
+    /// A view of the leading `rank`×`rank` principal submatrix without copying
+    pub fn leading(&self, rank: usize) -> Result<UpperTriView<'_, T, DynSquare>, UpperTriError> {
+        self.as_view().leading(rank)
+    }
+}
+
+impl<T, D: SquareDimension, S: PackedStorageMut<T>> UpperTriRawData<T, D, S> {
+    pub fn as_view_mut(&mut self) -> UpperTriViewMut<'_, T, D> {
+        UpperTriViewMut {
+            buf: self.buf.as_mut_slice(),
+            rank: self.rank,
+        }
+    }
+
+    pub fn leading_mut(
+        &mut self,
+        rank: usize,
+    ) -> Result<UpperTriViewMut<'_, T, DynSquare>, UpperTriError> {
+        self.as_view_mut().into_leading(rank)
+    }
+}
+
+impl<'a, T: Display, D: SquareDimension> Display for UpperTriView<'a, T, D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::result::Result<(), core::fmt::Error> {
+        fmt_triangle(self, f)
+    }
+}
+
+impl<'a, T: Display, D: SquareDimension> Display for UpperTriViewMut<'a, T, D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::result::Result<(), core::fmt::Error> {
+        fmt_triangle(self, f)
+    }
+}
+
+/// Sum into a new matrix, leaving the borrowed elements untouched
+#[cfg(feature = "alloc")]
+impl<'a, 'm, T, D, M> Add<&'m M> for UpperTriView<'a, T, D>
+where
+    T: Clone + for<'b> AddAssign<&'b T>,
+    D: SquareDimension,
+    M: TriangularMatrix<T, Dim = D> + ?Sized,
+{
+    type Output = UpperTriRawData<T, D>;
+
+    fn add(self, rhs: &'m M) -> Self::Output {
+        super::add(&self, rhs)
+    }
+}
+
+/// Accumulate into borrowed elements, such as a block of a larger matrix
+impl<'a, 'm, T, D, M> AddAssign<&'m M> for UpperTriViewMut<'a, T, D>
+where
+    T: for<'b> AddAssign<&'b T>,
+    D: SquareDimension,
+    M: TriangularMatrix<T, Dim = D> + ?Sized,
+{
+    fn add_assign(&mut self, rhs: &'m M) {
+        self.iter_mut()
+            .zip(rhs.iter())
+            .for_each(|(left, right)| *left += right);
+    }
+}
+
+impl<'a, 'm, T, D, M> SubAssign<&'m M> for UpperTriViewMut<'a, T, D>
+where
+    T: for<'b> SubAssign<&'b T>,
+    D: SquareDimension,
+    M: TriangularMatrix<T, Dim = D> + ?Sized,
+{
+    fn sub_assign(&mut self, rhs: &'m M) {
+        self.iter_mut()
+            .zip(rhs.iter())
+            .for_each(|(left, right)| *left -= right);
+    }
+}